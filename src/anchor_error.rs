@@ -0,0 +1,105 @@
+/// An Anchor Error
+///
+/// Built from the logs Anchor emits when an instruction returns an error:
+///
+/// - `AnchorError thrown in <file>:<line>. Error Code: <name>. Error Number: <n>. Error Message: <msg>.`
+/// - `AnchorError caused by account: <account>. Error Code: <name>. Error Number: <n>. Error Message: <msg>.`
+/// - `AnchorError occurred. Error Code: <name>. Error Number: <n>. Error Message: <msg>.`
+///
+/// optionally followed by the compared values, either inline (`Left: <value>`)
+/// or on the next line for pubkeys (`Left:` then `<pubkey>`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnchorError {
+    pub error_code: String,
+    pub error_number: u32,
+    pub error_message: String,
+    pub source: Option<AnchorErrorSource>,
+    pub account: Option<String>,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// The source location an [`AnchorError`] was thrown from
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnchorErrorSource {
+    pub file: String,
+    pub line: u32,
+}
+
+impl AnchorError {
+    /// Parses a single `AnchorError ...` program log message.
+    ///
+    /// The compared values are logged separately, see [`AnchorError::from_msgs`].
+    pub fn parse(msg: &str) -> Option<Self> {
+        let rest = msg.trim().strip_prefix("AnchorError ")?;
+        let (origin, rest) = rest.split_once(". Error Code: ")?;
+        let (error_code, rest) = rest.split_once(". Error Number: ")?;
+        let (error_number, error_message) = rest.split_once(". Error Message: ")?;
+
+        let mut source = None;
+        let mut account = None;
+        if let Some(location) = origin.strip_prefix("thrown in ") {
+            let (file, line) = location.rsplit_once(':')?;
+            source = Some(AnchorErrorSource {
+                file: file.to_string(),
+                line: line.parse().ok()?,
+            });
+        } else if let Some(name) = origin.strip_prefix("caused by account: ") {
+            account = Some(name.to_string());
+        } else if origin != "occurred" {
+            return None;
+        }
+
+        Some(AnchorError {
+            error_code: error_code.to_string(),
+            error_number: error_number.parse().ok()?,
+            error_message: error_message
+                .strip_suffix('.')
+                .unwrap_or(error_message)
+                .to_string(),
+            source,
+            account,
+            left: None,
+            right: None,
+        })
+    }
+
+    /// Finds the last Anchor error in a frame's program log messages, along
+    /// with the `Left`/`Right` values logged right after it.
+    pub fn from_msgs<'a>(msgs: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut anchor_error: Option<Self> = None;
+        let mut msgs = msgs.into_iter();
+
+        while let Some(msg) = msgs.next() {
+            if let Some(parsed) = Self::parse(msg) {
+                anchor_error = Some(parsed);
+                continue;
+            }
+
+            let Some(anchor_error) = anchor_error.as_mut() else {
+                continue;
+            };
+
+            let msg = msg.trim();
+            if let Some(value) = msg.strip_prefix("Left:") {
+                anchor_error.left = compared_value(value, &mut msgs);
+            } else if let Some(value) = msg.strip_prefix("Right:") {
+                anchor_error.right = compared_value(value, &mut msgs);
+            }
+        }
+
+        anchor_error
+    }
+}
+
+/// Inline values follow the label, pubkeys are logged on the next line.
+fn compared_value<'a>(value: &str, msgs: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        msgs.next().map(|next| next.trim().to_string())
+    } else {
+        Some(value.to_string())
+    }
+}
@@ -0,0 +1,47 @@
+use std::{error::Error, path::PathBuf};
+
+use clap::Args;
+use sol_log_parser::{ComputeDiff, ComputeProfile, RegressionThreshold, StructuredParseOptions};
+
+use crate::{input::InputFormat, read_file};
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// File with the transactions before the change
+    baseline: PathBuf,
+    /// File with the transactions after the change
+    candidate: PathBuf,
+    /// Fail when a frame's mean consumption grows by more units than this
+    #[arg(long, default_value_t = 0)]
+    threshold_units: u64,
+    /// Fail when a frame's mean consumption grows by more percent than this
+    #[arg(long, default_value_t = 0.0)]
+    threshold_percent: f64,
+}
+
+/// Prints the compute unit differences between the two files, failing when
+/// any frame regressed past the threshold
+pub fn run(
+    args: &DiffArgs,
+    input_format: InputFormat,
+    options: StructuredParseOptions,
+) -> Result<(), Box<dyn Error>> {
+    let profile = |file: &PathBuf| -> Result<ComputeProfile, Box<dyn Error>> {
+        let mut profile = ComputeProfile::new();
+        for transaction in read_file(Some(file), input_format, options)? {
+            profile.add(&transaction.frames);
+        }
+        Ok(profile)
+    };
+    let diff = ComputeDiff::new(&profile(&args.baseline)?, &profile(&args.candidate)?);
+    print!("{diff}");
+
+    let threshold = RegressionThreshold::new()
+        .units(args.threshold_units)
+        .percent(args.threshold_percent);
+    let regressions = diff.regressions(&threshold).count();
+    if regressions > 0 {
+        return Err(format!("{regressions} frame(s) regressed past the threshold").into());
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::error::Error;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// How the input logs are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// JSON if the input starts with `[`, text otherwise
+    Auto,
+    /// One log per line, transactions separated by blank lines
+    Text,
+    /// An array of logs, or an array of arrays of logs for many transactions
+    Json,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonInput {
+    Transaction(Vec<String>),
+    Transactions(Vec<Vec<String>>),
+}
+
+/// Splits the input into transactions, each a list of log lines.
+pub fn read_transactions(
+    input: &str,
+    format: InputFormat,
+) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+    let format = match format {
+        InputFormat::Auto if input.trim_start().starts_with('[') => InputFormat::Json,
+        InputFormat::Auto => InputFormat::Text,
+        format => format,
+    };
+
+    match format {
+        InputFormat::Json => match serde_json::from_str(input)? {
+            JsonInput::Transaction(logs) => Ok(vec![logs]),
+            JsonInput::Transactions(transactions) => Ok(transactions),
+        },
+        _ => Ok(read_text(input)),
+    }
+}
+
+fn read_text(input: &str) -> Vec<Vec<String>> {
+    let mut transactions = vec![];
+    let mut current = vec![];
+
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                transactions.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line.to_string());
+        }
+    }
+    if !current.is_empty() {
+        transactions.push(current);
+    }
+
+    transactions
+}
@@ -0,0 +1,242 @@
+//! Parses solana runtime logs from stdin or a file and prints them as
+//! structured call trees.
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{
+    error::ErrorKind, parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches, Parser,
+    Subcommand, ValueEnum,
+};
+use sol_log_parser::{
+    ChromeTrace, FoldedStacks, GraphFormatter, LogParseError, ParsedLog, ParsedTransactionLog,
+    RawLog, StructuredParseMode, StructuredParseOptions, TreeFormatter,
+};
+
+use diff::DiffArgs;
+use input::{read_transactions, InputFormat};
+
+mod diff;
+mod input;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Parse solana runtime logs into structured call trees"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// File to read logs from, stdin when omitted or `-`
+    file: Option<PathBuf>,
+    /// How the input logs are laid out
+    #[arg(long, global = true, value_enum, default_value_t = InputFormat::Auto)]
+    input: InputFormat,
+    /// How the structured logs are printed
+    #[arg(long, value_enum, default_value_t = OutputFormat::Tree)]
+    format: OutputFormat,
+    /// How strictly malformed or truncated logs are treated
+    #[arg(long, global = true, value_enum, default_value_t = Mode::Strict)]
+    mode: Mode,
+    /// Draw trees with ASCII instead of box-drawing characters
+    #[arg(long)]
+    ascii: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compare the compute units used by two sets of transactions
+    Diff(DiffArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// An indented call tree per transaction
+    Tree,
+    /// A pretty printed JSON array with one entry per transaction
+    Json,
+    /// One JSON line per transaction
+    Ndjson,
+    /// Folded stacks of the compute units used by all transactions, for flamegraphs
+    Folded,
+    /// Chrome Trace Event JSON with one track per transaction
+    ChromeTrace,
+    /// A Graphviz DOT call graph per transaction
+    Dot,
+    /// A Mermaid flowchart per transaction
+    Mermaid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    Strict,
+    Lenient,
+    BestEffort,
+}
+
+impl From<Mode> for StructuredParseOptions {
+    fn from(value: Mode) -> Self {
+        let mode = match value {
+            Mode::Strict => StructuredParseMode::Strict,
+            Mode::Lenient => StructuredParseMode::Lenient,
+            Mode::BestEffort => StructuredParseMode::BestEffort,
+        };
+        StructuredParseOptions { mode }
+    }
+}
+
+/// The arguments that only apply when no subcommand is given
+const TOP_LEVEL_ARGS: [(&str, &str); 3] = [
+    ("file", "[FILE]"),
+    ("format", "--format"),
+    ("ascii", "--ascii"),
+];
+
+/// Parses the arguments, rejecting top-level ones that a subcommand would
+/// otherwise silently ignore
+fn parse_args() -> Args {
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    if let Some((subcommand, _)) = matches.subcommand() {
+        if let Some((_, arg)) = TOP_LEVEL_ARGS
+            .into_iter()
+            .find(|&(id, _)| explicitly_set(&matches, id))
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "the argument '{arg}' cannot be used with the '{subcommand}' subcommand"
+                    ),
+                )
+                .exit();
+        }
+    }
+    args
+}
+
+fn explicitly_set(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn main() -> ExitCode {
+    match run(parse_args()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if let Some(Command::Diff(diff_args)) = &args.command {
+        return diff::run(diff_args, args.input, args.mode.into());
+    }
+
+    let transactions = read_file(args.file.as_deref(), args.input, args.mode.into())?;
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Tree => {
+            let tree = TreeFormatter::new().ascii(args.ascii);
+            for (index, transaction) in transactions.iter().enumerate() {
+                if index > 0 {
+                    writeln!(out)?;
+                }
+                // orphans are printed between the frames they were logged between
+                let mut orphans = transaction.orphans.iter().peekable();
+                for (position, frame) in transaction.frames.iter().enumerate() {
+                    while let Some(orphan) = orphans.next_if(|orphan| orphan.position == position) {
+                        writeln!(out, "{}", orphan.log)?;
+                    }
+                    writeln!(out, "{}", tree.display(frame))?;
+                }
+                for orphan in orphans {
+                    writeln!(out, "{}", orphan.log)?;
+                }
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &transactions)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => {
+            for transaction in &transactions {
+                serde_json::to_writer(&mut out, transaction)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Folded => {
+            let mut folded = FoldedStacks::new().instruction_names(true);
+            for transaction in &transactions {
+                folded.add_all(&transaction.frames);
+            }
+            write!(out, "{folded}")?;
+        }
+        OutputFormat::ChromeTrace => {
+            let mut trace = ChromeTrace::new();
+            for transaction in &transactions {
+                trace.add(&transaction.frames);
+            }
+            writeln!(out, "{trace}")?;
+        }
+        OutputFormat::Dot | OutputFormat::Mermaid => {
+            let graph = GraphFormatter::new();
+            for (index, transaction) in transactions.iter().enumerate() {
+                if index > 0 {
+                    writeln!(out)?;
+                }
+                match args.format {
+                    OutputFormat::Dot => writeln!(out, "{}", graph.dot(&transaction.frames))?,
+                    _ => writeln!(out, "{}", graph.mermaid(&transaction.frames))?,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads and parses every transaction in `file`, or stdin when it is `None` or `-`
+fn read_file(
+    file: Option<&Path>,
+    input_format: InputFormat,
+    options: StructuredParseOptions,
+) -> Result<Vec<ParsedTransactionLog>, Box<dyn Error>> {
+    let input = match file {
+        Some(path) if path.as_os_str() != "-" => fs::read_to_string(path)?,
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+
+    let mut transactions = vec![];
+    for (index, logs) in read_transactions(&input, input_format)?.iter().enumerate() {
+        let transaction = parse_transaction(logs, options)
+            .map_err(|err| format!("transaction {index}: {err}"))?;
+        for warning in &transaction.warnings {
+            eprintln!("warning: transaction {index}: {warning}");
+        }
+        transactions.push(transaction);
+    }
+    Ok(transactions)
+}
+
+fn parse_transaction(
+    logs: &[String],
+    options: StructuredParseOptions,
+) -> Result<ParsedTransactionLog, LogParseError> {
+    let parsed_logs = logs
+        .iter()
+        .map(|log| ParsedLog::from_raw(&RawLog::parse(log)))
+        .collect::<Result<Vec<_>, _>>()?;
+    ParsedTransactionLog::from_parsed_logs_with_options(parsed_logs, options)
+}
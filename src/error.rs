@@ -8,4 +8,48 @@ pub enum LogParseError {
     Int(#[from] ParseIntError),
     #[error(transparent)]
     Base64(#[from] base64::DecodeError),
+    /// Malformed JSON input, boxed so the variant exists without the `rpc` feature
+    #[error(transparent)]
+    Json(Box<dyn std::error::Error + Send + Sync>),
+    /// An RPC response without any log messages to parse
+    #[error("rpc response has no log messages")]
+    MissingLogMessages,
+    /// A JSON-RPC response carrying an `error` instead of a `result`
+    #[error("rpc error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    /// A JSON-RPC response whose `result` is null, e.g. for a transaction
+    /// that is unknown or not yet confirmed
+    #[error("rpc response has a null result")]
+    NullResult,
+    /// A `success` log with no open invoke frame to close
+    #[error("unmatched success log at line {index}: {log}")]
+    UnmatchedSuccess { index: usize, log: String },
+    /// A `failed` log with no open invoke frame to close
+    #[error("unmatched failed log at line {index}: {log}")]
+    UnmatchedFailed { index: usize, log: String },
+    /// A `success` or `failed` log closing a frame invoked by another program
+    #[error("mismatched program id at line {index}: expected {expected}, found {found}: {log}")]
+    MismatchedProgramId {
+        index: usize,
+        log: String,
+        expected: String,
+        found: String,
+    },
+    /// Frames still open once all logs were consumed, reported at the innermost one
+    #[error(
+        "unbalanced log stack: {open} frame(s) left open, innermost invoked at line {index}: {log}"
+    )]
+    UnbalancedStack {
+        index: usize,
+        log: String,
+        open: usize,
+    },
+    /// An invoke whose depth does not follow from the currently open frames
+    #[error("invoke depth mismatch at line {index}: expected {expected}, found {found}: {log}")]
+    DepthMismatch {
+        index: usize,
+        log: String,
+        expected: usize,
+        found: usize,
+    },
 }
@@ -0,0 +1,166 @@
+use std::fmt::{self, Display};
+
+macro_rules! instruction_errors {
+    ($($variant:ident => $msg:literal,)*) => {
+        /// A Parsed Instruction Error
+        ///
+        /// Mirrors the runtime's `InstructionError` as displayed in
+        /// `Program <id> failed: <err>` logs.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum ParsedInstructionError {
+            $(
+                #[doc = concat!("`", $msg, "`")]
+                $variant,
+            )*
+            /// `custom program error: 0x<code>`
+            Custom(u32),
+            /// `Failed to serialize or deserialize account data: <msg>`
+            BorshIoError(String),
+            /// `Access violation in <section> at address 0x<address> of size <size>`
+            AccessViolation {
+                section: String,
+                address: u64,
+                size: u64,
+            },
+            /// Any error not recognized above
+            Unknown(String),
+        }
+
+        impl ParsedInstructionError {
+            fn from_message(msg: &str) -> Option<Self> {
+                match msg {
+                    $($msg => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn message(&self) -> Option<&'static str> {
+                match self {
+                    $(Self::$variant => Some($msg),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+instruction_errors! {
+    GenericError => "generic instruction error",
+    InvalidArgument => "invalid program argument",
+    InvalidInstructionData => "invalid instruction data",
+    InvalidAccountData => "invalid account data for instruction",
+    AccountDataTooSmall => "account data too small for instruction",
+    InsufficientFunds => "insufficient funds for instruction",
+    IncorrectProgramId => "incorrect program id for instruction",
+    MissingRequiredSignature => "missing required signature for instruction",
+    AccountAlreadyInitialized => "instruction requires an uninitialized account",
+    UninitializedAccount => "instruction requires an initialized account",
+    UnbalancedInstruction => "sum of account balances before and after instruction do not match",
+    ModifiedProgramId => "instruction illegally modified the program id of an account",
+    ExternalAccountLamportSpend => "instruction spent from the balance of an account it does not own",
+    ExternalAccountDataModified => "instruction modified data of an account it does not own",
+    ReadonlyLamportChange => "instruction changed the balance of a read-only account",
+    ReadonlyDataModified => "instruction modified data of a read-only account",
+    DuplicateAccountIndex => "instruction contains duplicate accounts",
+    ExecutableModified => "instruction changed executable bit of an account",
+    RentEpochModified => "instruction modified rent epoch of an account",
+    NotEnoughAccountKeys => "insufficient account keys for instruction",
+    AccountDataSizeChanged => "program other than the account's owner changed the size of the account data",
+    AccountNotExecutable => "instruction expected an executable account",
+    AccountBorrowFailed => "instruction tries to borrow reference for an account which is already borrowed",
+    AccountBorrowOutstanding => "instruction left account with an outstanding borrowed reference",
+    DuplicateAccountOutOfSync => "instruction modifications of multiply-passed account differ",
+    InvalidError => "program returned invalid error code",
+    ExecutableDataModified => "instruction changed executable accounts data",
+    ExecutableLamportChange => "instruction changed the balance of an executable account",
+    ExecutableAccountNotRentExempt => "executable accounts must be rent exempt",
+    UnsupportedProgramId => "Unsupported program id",
+    CallDepth => "Cross-program invocation call depth too deep",
+    MissingAccount => "An account required by the instruction is missing",
+    ReentrancyNotAllowed => "Cross-program invocation reentrancy not allowed for this instruction",
+    MaxSeedLengthExceeded => "Length of the seed is too long for address generation",
+    InvalidSeeds => "Provided seeds do not result in a valid address",
+    InvalidRealloc => "Failed to reallocate account data",
+    ComputationalBudgetExceeded => "Computational budget exceeded",
+    PrivilegeEscalation => "Cross-program invocation with unauthorized signer or writable account",
+    ProgramEnvironmentSetupFailure => "Failed to create program execution environment",
+    ProgramFailedToComplete => "Program failed to complete",
+    ProgramFailedToCompile => "Program failed to compile",
+    Immutable => "Account is immutable",
+    IncorrectAuthority => "Incorrect authority provided",
+    AccountNotRentExempt => "An account does not have enough lamports to be rent-exempt",
+    InvalidAccountOwner => "Invalid account owner",
+    ArithmeticOverflow => "Program arithmetic overflowed",
+    UnsupportedSysvar => "Unsupported sysvar",
+    IllegalOwner => "Provided owner is not allowed",
+    MaxAccountsDataAllocationsExceeded => "Accounts data allocations exceeded the maximum allowed per transaction",
+    MaxAccountsExceeded => "Max accounts exceeded",
+    MaxInstructionTraceLengthExceeded => "Max instruction trace length exceeded",
+    BuiltinProgramsMustConsumeComputeUnits => "Builtin programs must consume compute units",
+}
+
+impl ParsedInstructionError {
+    pub fn parse(err: &str) -> Self {
+        let err = err.trim();
+
+        if let Some(known) = Self::from_message(err) {
+            return known;
+        }
+
+        if let Some(code) = err
+            .strip_prefix("custom program error: 0x")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        {
+            return Self::Custom(code);
+        }
+
+        if let Some(msg) = err.strip_prefix("Failed to serialize or deserialize account data: ") {
+            return Self::BorshIoError(msg.to_string());
+        }
+
+        if let Some(access_violation) = Self::parse_access_violation(err) {
+            return access_violation;
+        }
+
+        Self::Unknown(err.to_string())
+    }
+
+    /// `Access violation in <section> at address 0x<address> of size <size>`
+    fn parse_access_violation(err: &str) -> Option<Self> {
+        let rest = err.strip_prefix("Access violation in ")?;
+        let (section, rest) = rest.split_once(" at address 0x")?;
+        let (address, rest) = rest.split_once(" of size ")?;
+        let size = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .filter(|size| !size.is_empty())?;
+
+        Some(Self::AccessViolation {
+            section: section.to_string(),
+            address: u64::from_str_radix(address, 16).ok()?,
+            size: size.parse().ok()?,
+        })
+    }
+}
+
+impl Display for ParsedInstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom(code) => write!(f, "custom program error: {code:#x}"),
+            Self::BorshIoError(msg) => {
+                write!(f, "Failed to serialize or deserialize account data: {msg}")
+            }
+            Self::AccessViolation {
+                section,
+                address,
+                size,
+            } => write!(
+                f,
+                "Access violation in {section} at address {address:#x} of size {size}"
+            ),
+            Self::Unknown(err) => f.write_str(err),
+            known => f.write_str(known.message().unwrap_or_default()),
+        }
+    }
+}
@@ -1,12 +1,36 @@
 //! A small utility crate for parsing solana logs
+pub use anchor_error::AnchorError;
 pub use error::LogParseError;
+pub use instruction_error::ParsedInstructionError;
 pub use parsed_log::ParsedLog;
+pub use program_panic::ProgramPanic;
 pub use raw_log::RawLog;
-pub use structured_log::{parsed::ParsedStructuredLog, raw::RawStructuredLog};
+pub use structured_log::{
+    budget::BudgetAnalyzer,
+    call_graph::CallGraph,
+    chrome_trace::ChromeTrace,
+    compute_stats::ComputeStats,
+    diff::{ComputeDiff, ComputeProfile, RegressionThreshold},
+    folded::FoldedStacks,
+    graph::GraphFormatter,
+    options::{StructuredParseMode, StructuredParseOptions},
+    parsed::{ParsedStructuredLog, ParsedTransactionLog},
+    raw::{RawStructuredLog, RawTransactionLog},
+    tree::TreeFormatter,
+    view::FrameView,
+    warning::StructuredParseWarning,
+};
 
+pub mod anchor_error;
 pub mod error;
+pub mod instruction_error;
 pub mod parsed_log;
+pub mod program_panic;
 pub mod raw_log;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
 pub mod structured_log;
 
 pub type Result<T> = std::result::Result<T, LogParseError>;
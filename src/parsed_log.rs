@@ -4,14 +4,18 @@ use base64::{prelude::BASE64_STANDARD, Engine};
 use solana_pubkey::Pubkey;
 
 use crate::{
+    anchor_error::AnchorError,
+    instruction_error::ParsedInstructionError,
+    program_panic::ProgramPanic,
     raw_log::{
-        RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawLog, RawOtherLog, RawProgramLog,
-        RawReturnLog, RawSuccessLog,
+        RawConsumptionLog, RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawLog, RawOtherLog,
+        RawProgramLog, RawReturnLog, RawSuccessLog, RawTruncatedLog,
     },
     Result,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ParsedLog {
     Invoke(ParsedInvokeLog),
     Success(ParsedSuccessLog),
@@ -20,6 +24,8 @@ pub enum ParsedLog {
     Data(ParsedDataLog),
     Return(ParsedReturnLog),
     Cu(ParsedCuLog),
+    Consumption(ParsedConsumptionLog),
+    Truncated(ParsedTruncatedLog),
     Other(ParsedOtherLog),
 }
 
@@ -33,6 +39,8 @@ impl ParsedLog {
             RawLog::Data(log) => ParsedDataLog::from_raw(log).map(ParsedLog::from),
             RawLog::Return(log) => ParsedReturnLog::from_raw(log).map(ParsedLog::from),
             RawLog::Cu(log) => ParsedCuLog::from_raw(log).map(ParsedLog::from),
+            RawLog::Consumption(log) => ParsedConsumptionLog::from_raw(log).map(ParsedLog::from),
+            RawLog::Truncated(log) => ParsedTruncatedLog::from_raw(log).map(ParsedLog::from),
             RawLog::Other(log) => ParsedOtherLog::from_raw(log).map(ParsedLog::from),
         }
     }
@@ -74,6 +82,17 @@ impl From<ParsedCuLog> for ParsedLog {
         ParsedLog::Cu(value)
     }
 }
+impl From<ParsedConsumptionLog> for ParsedLog {
+    fn from(value: ParsedConsumptionLog) -> Self {
+        ParsedLog::Consumption(value)
+    }
+}
+
+impl From<ParsedTruncatedLog> for ParsedLog {
+    fn from(value: ParsedTruncatedLog) -> Self {
+        ParsedLog::Truncated(value)
+    }
+}
 
 impl From<ParsedOtherLog> for ParsedLog {
     fn from(value: ParsedOtherLog) -> Self {
@@ -84,8 +103,10 @@ impl From<ParsedOtherLog> for ParsedLog {
 ///
 /// `Program <id> invoke [n]`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedInvokeLog {
     pub raw: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub program_id: Pubkey,
     pub depth: u8,
 }
@@ -104,8 +125,10 @@ impl ParsedInvokeLog {
 ///
 /// `Program <id> success`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedSuccessLog {
     pub raw: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub program_id: Pubkey,
 }
 
@@ -122,10 +145,12 @@ impl ParsedSuccessLog {
 ///
 /// `Program <id> failed: <err>`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedFailedLog {
     pub raw: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub program_id: Pubkey,
-    pub err: String,
+    pub err: ParsedInstructionError,
 }
 
 impl ParsedFailedLog {
@@ -133,7 +158,7 @@ impl ParsedFailedLog {
         Ok(ParsedFailedLog {
             raw: log.raw.to_string(),
             program_id: Pubkey::from_str(log.program_id)?,
-            err: log.err.to_string(),
+            err: ParsedInstructionError::parse(log.err),
         })
     }
 }
@@ -142,6 +167,7 @@ impl ParsedFailedLog {
 ///
 /// `Program log: <msg>`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedProgramLog {
     pub raw: String,
     pub msg: String,
@@ -154,33 +180,56 @@ impl ParsedProgramLog {
             msg: log.msg.to_string(),
         })
     }
+
+    /// The Anchor error header logged by this message, if any
+    pub fn anchor_error(&self) -> Option<AnchorError> {
+        AnchorError::parse(&self.msg)
+    }
+
+    /// The panic logged by this message, if any
+    pub fn panic(&self) -> Option<ProgramPanic> {
+        ProgramPanic::parse(&self.msg)
+    }
 }
 
 // A Program Data Log
 ///
-/// `Program data: <base64>`
+/// `Program data: <base64> [<base64> ...]`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedDataLog {
     pub raw: String,
-    pub data: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::bytes_vec"))]
+    pub data: Vec<Vec<u8>>,
 }
 
 impl ParsedDataLog {
     pub fn from_raw(log: &RawDataLog) -> Result<Self> {
         Ok(ParsedDataLog {
             raw: log.raw.to_string(),
-            data: BASE64_STANDARD.decode(log.data)?,
+            data: log
+                .fields()
+                .map(|field| BASE64_STANDARD.decode(field))
+                .collect::<std::result::Result<_, _>>()?,
         })
     }
+
+    /// All decoded fields concatenated into a single buffer
+    pub fn flattened(&self) -> Vec<u8> {
+        self.data.concat()
+    }
 }
 
 // A Program Return Log
 ///
 /// `Program return: <id> <base64>`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedReturnLog {
     pub raw: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub program_id: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::bytes"))]
     pub data: Vec<u8>,
 }
 
@@ -198,8 +247,10 @@ impl ParsedReturnLog {
 ///
 /// `Program <id> consumed <x> of <y> compute units`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedCuLog {
     pub raw: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub program_id: Pubkey,
     pub consumed: u64,
     pub budget: u64,
@@ -216,7 +267,44 @@ impl ParsedCuLog {
     }
 }
 
+// A Program Consumption Log
+///
+/// `Program consumption: <n> units remaining`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedConsumptionLog {
+    pub raw: String,
+    pub remaining: u64,
+}
+
+impl ParsedConsumptionLog {
+    pub fn from_raw(log: &RawConsumptionLog) -> Result<Self> {
+        Ok(ParsedConsumptionLog {
+            raw: log.raw.to_string(),
+            remaining: log.remaining,
+        })
+    }
+}
+
+// A Truncated Log
+///
+/// `Log truncated`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedTruncatedLog {
+    pub raw: String,
+}
+
+impl ParsedTruncatedLog {
+    pub fn from_raw(log: &RawTruncatedLog) -> Result<Self> {
+        Ok(ParsedTruncatedLog {
+            raw: log.raw.to_string(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedOtherLog {
     pub raw: String,
 }
@@ -236,13 +324,16 @@ impl ParsedOtherLog {
 mod helper_code {
     use solana_pubkey::Pubkey;
 
-    use crate::structured_log::{
-        ComputeUnitsLog, FailedLog, InvokeLog, Log, ReturnLog, SuccessLog,
+    use crate::{
+        instruction_error::ParsedInstructionError,
+        structured_log::{
+            ComputeUnitsLog, ConsumptionLog, FailedLog, InvokeLog, Log, ReturnLog, SuccessLog,
+        },
     };
 
     use super::{
-        ParsedCuLog, ParsedDataLog, ParsedFailedLog, ParsedInvokeLog, ParsedOtherLog,
-        ParsedProgramLog, ParsedReturnLog, ParsedSuccessLog,
+        ParsedConsumptionLog, ParsedCuLog, ParsedDataLog, ParsedFailedLog, ParsedInvokeLog,
+        ParsedOtherLog, ParsedProgramLog, ParsedReturnLog, ParsedSuccessLog, ParsedTruncatedLog,
     };
 
     impl Log for ParsedInvokeLog {
@@ -301,6 +392,22 @@ mod helper_code {
         }
     }
 
+    impl Log for ParsedConsumptionLog {
+        type RawLog = String;
+
+        fn raw_log(&self) -> Self::RawLog {
+            self.raw.clone()
+        }
+    }
+
+    impl Log for ParsedTruncatedLog {
+        type RawLog = String;
+
+        fn raw_log(&self) -> Self::RawLog {
+            self.raw.clone()
+        }
+    }
+
     impl Log for ParsedOtherLog {
         type RawLog = String;
 
@@ -331,7 +438,7 @@ mod helper_code {
 
     impl FailedLog for ParsedFailedLog {
         type ProgramId = Pubkey;
-        type Err = String;
+        type Err = ParsedInstructionError;
 
         fn program_id(&self) -> Self::ProgramId {
             self.program_id
@@ -362,4 +469,10 @@ mod helper_code {
             self.program_id
         }
     }
+
+    impl ConsumptionLog for ParsedConsumptionLog {
+        fn remaining(&self) -> u64 {
+            self.remaining
+        }
+    }
 }
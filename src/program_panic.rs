@@ -0,0 +1,46 @@
+/// A Program Panic
+///
+/// Built from the message logged by a program's panic handler, in either the
+/// older or the newer Rust format:
+///
+/// - `panicked at '<message>', <file>:<line>:<column>`
+/// - `panicked at <file>:<line>:<column>:\n<message>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgramPanic {
+    pub message: String,
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl ProgramPanic {
+    /// Parses a single program log message.
+    ///
+    /// When the newer format is split across log lines the message is left
+    /// empty, as it was logged on its own line.
+    pub fn parse(msg: &str) -> Option<Self> {
+        let rest = msg.trim().strip_prefix("panicked at ")?;
+
+        let (message, location) = match rest.strip_prefix('\'') {
+            Some(quoted) => quoted.rsplit_once("', ")?,
+            None => match rest.split_once('\n') {
+                Some((location, message)) => (message.trim(), location.trim_end()),
+                None => ("", rest),
+            },
+        };
+        let location = location.strip_suffix(':').unwrap_or(location);
+
+        let mut parts = location.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next()?;
+
+        Some(ProgramPanic {
+            message: message.to_string(),
+            file: file.to_string(),
+            line,
+            column,
+        })
+    }
+}
@@ -1,22 +1,36 @@
-use crate::quick_pubkey_check;
+use std::str::SplitAsciiWhitespace;
+
+use crate::{anchor_error::AnchorError, program_panic::ProgramPanic, quick_pubkey_check};
 
 /// A Raw Log
+///
+/// With the `serde` feature the fields are deserialized zero-copy, so the input
+/// must outlive the log and contain no escaped strings: JSON like
+/// `"Program log: \"quoted\""` fails to deserialize into a `RawLog`.
+/// Deserialize into [`ParsedLog`](crate::ParsedLog) to accept any input.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RawLog<'a> {
-    Invoke(RawInvokeLog<'a>),
-    Success(RawSuccessLog<'a>),
-    Failed(RawFailedLog<'a>),
-    Log(RawProgramLog<'a>),
-    Data(RawDataLog<'a>),
-    Return(RawReturnLog<'a>),
-    Cu(RawCuLog<'a>),
-    Other(RawOtherLog<'a>),
+    Invoke(#[cfg_attr(feature = "serde", serde(borrow))] RawInvokeLog<'a>),
+    Success(#[cfg_attr(feature = "serde", serde(borrow))] RawSuccessLog<'a>),
+    Failed(#[cfg_attr(feature = "serde", serde(borrow))] RawFailedLog<'a>),
+    Log(#[cfg_attr(feature = "serde", serde(borrow))] RawProgramLog<'a>),
+    Data(#[cfg_attr(feature = "serde", serde(borrow))] RawDataLog<'a>),
+    Return(#[cfg_attr(feature = "serde", serde(borrow))] RawReturnLog<'a>),
+    Cu(#[cfg_attr(feature = "serde", serde(borrow))] RawCuLog<'a>),
+    Consumption(#[cfg_attr(feature = "serde", serde(borrow))] RawConsumptionLog<'a>),
+    Truncated(#[cfg_attr(feature = "serde", serde(borrow))] RawTruncatedLog<'a>),
+    Other(#[cfg_attr(feature = "serde", serde(borrow))] RawOtherLog<'a>),
 }
 
 impl<'a> RawLog<'a> {
     pub fn parse(log: &'a str) -> Self {
         let trimmed = log.trim();
 
+        if trimmed == "Log truncated" {
+            return RawLog::Truncated(RawTruncatedLog { raw: log });
+        }
+
         if let Some(rest) = trimmed.strip_prefix("Program log: ") {
             return RawLog::Log(RawProgramLog {
                 raw: log,
@@ -31,6 +45,19 @@ impl<'a> RawLog<'a> {
             });
         }
 
+        if let Some(rest) = trimmed.strip_prefix("Program consumption: ") {
+            return rest
+                .strip_suffix(" units remaining")
+                .and_then(|remaining| remaining.parse().ok())
+                .map(|remaining| {
+                    RawLog::Consumption(RawConsumptionLog {
+                        raw: log,
+                        remaining,
+                    })
+                })
+                .unwrap_or(RawLog::Other(RawOtherLog { raw: log }));
+        }
+
         if let Some(rest) = trimmed.strip_prefix("Program return: ") {
             let Some((program_id, data)) = rest.split_once(' ') else {
                 return RawLog::Other(RawOtherLog { raw: log });
@@ -117,6 +144,7 @@ impl<'a> RawLog<'a> {
 ///
 /// `Program <id> invoke [n]`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawInvokeLog<'a> {
     pub raw: &'a str,
     pub program_id: &'a str,
@@ -127,6 +155,7 @@ pub struct RawInvokeLog<'a> {
 ///
 /// `Program <id> success`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawSuccessLog<'a> {
     pub raw: &'a str,
     pub program_id: &'a str,
@@ -136,6 +165,7 @@ pub struct RawSuccessLog<'a> {
 ///
 /// `Program <id> failed: <err>``
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawFailedLog<'a> {
     pub raw: &'a str,
     pub program_id: &'a str,
@@ -146,24 +176,46 @@ pub struct RawFailedLog<'a> {
 ///
 /// `Program log: <msg>`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawProgramLog<'a> {
     pub raw: &'a str,
     pub msg: &'a str,
 }
 
+impl RawProgramLog<'_> {
+    /// The Anchor error header logged by this message, if any
+    pub fn anchor_error(&self) -> Option<AnchorError> {
+        AnchorError::parse(self.msg)
+    }
+
+    /// The panic logged by this message, if any
+    pub fn panic(&self) -> Option<ProgramPanic> {
+        ProgramPanic::parse(self.msg)
+    }
+}
+
 /// A Raw Data Log
 ///
-/// `Program data: <base64>`
+/// `Program data: <base64> [<base64> ...]`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawDataLog<'a> {
     pub raw: &'a str,
     pub data: &'a str,
 }
 
+impl<'a> RawDataLog<'a> {
+    /// The space separated base64 fields, one per slice passed to `sol_log_data`
+    pub fn fields(&self) -> SplitAsciiWhitespace<'a> {
+        self.data.split_ascii_whitespace()
+    }
+}
+
 /// A Raw Return Log
 ///
 /// `Program return: <id> <base64>`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawReturnLog<'a> {
     pub raw: &'a str,
     pub program_id: &'a str,
@@ -174,6 +226,7 @@ pub struct RawReturnLog<'a> {
 ///
 /// `Program <id> consumed <x> of <y> compute units`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawCuLog<'a> {
     pub raw: &'a str,
     pub program_id: &'a str,
@@ -181,7 +234,27 @@ pub struct RawCuLog<'a> {
     pub budget: u64,
 }
 
+/// A Raw Consumption Log
+///
+/// `Program consumption: <n> units remaining`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawConsumptionLog<'a> {
+    pub raw: &'a str,
+    pub remaining: u64,
+}
+
+/// A Raw Truncated Log
+///
+/// `Log truncated`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawTruncatedLog<'a> {
+    pub raw: &'a str,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawOtherLog<'a> {
     pub raw: &'a str,
 }
@@ -192,12 +265,12 @@ pub struct RawOtherLog<'a> {
 
 mod helper_code {
     use crate::structured_log::{
-        ComputeUnitsLog, FailedLog, InvokeLog, Log, ReturnLog, SuccessLog,
+        ComputeUnitsLog, ConsumptionLog, FailedLog, InvokeLog, Log, ReturnLog, SuccessLog,
     };
 
     use super::{
-        RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawOtherLog, RawProgramLog, RawReturnLog,
-        RawSuccessLog,
+        RawConsumptionLog, RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawOtherLog,
+        RawProgramLog, RawReturnLog, RawSuccessLog, RawTruncatedLog,
     };
 
     impl<'a> Log for RawInvokeLog<'a> {
@@ -256,6 +329,22 @@ mod helper_code {
         }
     }
 
+    impl<'a> Log for RawConsumptionLog<'a> {
+        type RawLog = &'a str;
+
+        fn raw_log(&self) -> Self::RawLog {
+            self.raw
+        }
+    }
+
+    impl<'a> Log for RawTruncatedLog<'a> {
+        type RawLog = &'a str;
+
+        fn raw_log(&self) -> Self::RawLog {
+            self.raw
+        }
+    }
+
     impl<'a> Log for RawOtherLog<'a> {
         type RawLog = &'a str;
 
@@ -317,4 +406,10 @@ mod helper_code {
             self.program_id
         }
     }
+
+    impl ConsumptionLog for RawConsumptionLog<'_> {
+        fn remaining(&self) -> u64 {
+            self.remaining
+        }
+    }
 }
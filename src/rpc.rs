@@ -0,0 +1,289 @@
+//! Ingestion of `getTransaction` and `simulateTransaction` RPC responses.
+//!
+//! Both responses can be read either as the full JSON-RPC envelope
+//! (`{"jsonrpc": "2.0", "result": ..., "id": 1}`) or as the bare `result`.
+//! An envelope carrying an `error`, or a `null` result such as `getTransaction`
+//! returns for an unknown or unconfirmed transaction, is reported as such.
+
+use std::{io::Read, str::FromStr};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    structured_log::{options::StructuredParseOptions, FrameEvent},
+    LogParseError, ParsedLog, ParsedStructuredLog, ParsedTransactionLog, RawLog, Result,
+};
+
+/// The `result` of a `getTransaction` request
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionResponse {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub meta: Option<RpcTransactionMeta>,
+    /// The transaction in whichever encoding was requested
+    pub transaction: Option<serde_json::Value>,
+}
+
+/// The `meta` of a `getTransaction` result
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionMeta {
+    pub err: Option<serde_json::Value>,
+    pub log_messages: Option<Vec<String>>,
+    pub compute_units_consumed: Option<u64>,
+    pub return_data: Option<RpcReturnData>,
+}
+
+/// The `result` of a `simulateTransaction` request
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SimulateTransactionResponse {
+    pub context: Option<RpcContext>,
+    pub value: SimulateTransactionValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RpcContext {
+    pub slot: u64,
+}
+
+/// The `value` of a `simulateTransaction` result
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateTransactionValue {
+    pub err: Option<serde_json::Value>,
+    pub logs: Option<Vec<String>>,
+    pub units_consumed: Option<u64>,
+    pub return_data: Option<RpcReturnData>,
+}
+
+/// Return data as encoded by the RPC: `{"programId": ..., "data": [<data>, "base64"]}`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcReturnData {
+    pub program_id: String,
+    pub data: (String, String),
+}
+
+impl RpcReturnData {
+    pub fn decode(&self) -> Result<ReturnData> {
+        Ok(ReturnData {
+            program_id: Pubkey::from_str(&self.program_id)?,
+            data: BASE64_STANDARD.decode(&self.data.0)?,
+        })
+    }
+}
+
+/// Decoded transaction return data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReturnData {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// A disagreement between what the RPC reported and what was rebuilt from the logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcMismatch {
+    /// The consumed units of the top-level frames do not add up to the reported units.
+    ///
+    /// Builtin programs do not log their consumption, so a shortfall is only
+    /// reported when every top-level frame logged its consumed units.
+    UnitsConsumed { reported: u64, reconstructed: u64 },
+    /// The last `Program return` log does not match the reported return data
+    ReturnData {
+        reported: Option<ReturnData>,
+        reconstructed: Option<ReturnData>,
+    },
+}
+
+/// A transaction's structured logs rebuilt from an RPC response
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcTransactionLog {
+    pub transaction: ParsedTransactionLog,
+    pub units_consumed: Option<u64>,
+    pub return_data: Option<ReturnData>,
+    pub mismatches: Vec<RpcMismatch>,
+}
+
+/// The `error` of a JSON-RPC response
+#[derive(Deserialize)]
+struct RpcErrorObject {
+    code: i64,
+    message: String,
+}
+
+fn from_json_str<T: DeserializeOwned>(json: &str) -> Result<T> {
+    let value = serde_json::from_str(json).map_err(|err| LogParseError::Json(err.into()))?;
+    from_json_value(value)
+}
+
+fn from_json_reader<T: DeserializeOwned>(reader: impl Read) -> Result<T> {
+    let value = serde_json::from_reader(reader).map_err(|err| LogParseError::Json(err.into()))?;
+    from_json_value(value)
+}
+
+/// Unwraps the JSON-RPC envelope, if any, before deserializing the `result`
+fn from_json_value<T: DeserializeOwned>(mut value: serde_json::Value) -> Result<T> {
+    let result = match value.as_object_mut() {
+        Some(envelope) if envelope.contains_key("error") => {
+            let error: RpcErrorObject = deserialize(envelope.remove("error").unwrap_or_default())?;
+            return Err(LogParseError::Rpc {
+                code: error.code,
+                message: error.message,
+            });
+        }
+        Some(envelope) => envelope.remove("result"),
+        None => None,
+    };
+
+    match result.unwrap_or(value) {
+        serde_json::Value::Null => Err(LogParseError::NullResult),
+        result => deserialize(result),
+    }
+}
+
+/// Deserializes `value`, naming the path to the offending field on failure
+fn deserialize<T: DeserializeOwned>(value: serde_json::Value) -> Result<T> {
+    serde_path_to_error::deserialize(value).map_err(|err| LogParseError::Json(err.into()))
+}
+
+impl GetTransactionResponse {
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        from_json_str(json)
+    }
+
+    pub fn from_json_reader(reader: impl Read) -> Result<Self> {
+        from_json_reader(reader)
+    }
+
+    /// The first signature, when the transaction was requested as JSON
+    pub fn signature(&self) -> Option<&str> {
+        self.transaction
+            .as_ref()?
+            .pointer("/signatures/0")?
+            .as_str()
+    }
+
+    pub fn transaction_log(&self, options: StructuredParseOptions) -> Result<RpcTransactionLog> {
+        let meta = self
+            .meta
+            .as_ref()
+            .ok_or(LogParseError::MissingLogMessages)?;
+        let logs = meta
+            .log_messages
+            .as_deref()
+            .ok_or(LogParseError::MissingLogMessages)?;
+
+        let mut transaction_log = RpcTransactionLog::build(
+            logs,
+            meta.compute_units_consumed,
+            meta.return_data.as_ref(),
+            options,
+        )?;
+        transaction_log.transaction.signature = self.signature().map(String::from);
+        transaction_log.transaction.slot = Some(self.slot);
+        transaction_log.transaction.block_time = self.block_time;
+        Ok(transaction_log)
+    }
+}
+
+impl SimulateTransactionResponse {
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        from_json_str(json)
+    }
+
+    pub fn from_json_reader(reader: impl Read) -> Result<Self> {
+        from_json_reader(reader)
+    }
+
+    pub fn transaction_log(&self, options: StructuredParseOptions) -> Result<RpcTransactionLog> {
+        let logs = self
+            .value
+            .logs
+            .as_deref()
+            .ok_or(LogParseError::MissingLogMessages)?;
+
+        let mut transaction_log = RpcTransactionLog::build(
+            logs,
+            self.value.units_consumed,
+            self.value.return_data.as_ref(),
+            options,
+        )?;
+        transaction_log.transaction.slot = self.context.as_ref().map(|context| context.slot);
+        Ok(transaction_log)
+    }
+}
+
+impl RpcTransactionLog {
+    fn build(
+        logs: &[String],
+        units_consumed: Option<u64>,
+        return_data: Option<&RpcReturnData>,
+        options: StructuredParseOptions,
+    ) -> Result<Self> {
+        let parsed_logs = logs
+            .iter()
+            .map(|log| ParsedLog::from_raw(&RawLog::parse(log)))
+            .collect::<Result<Vec<_>>>()?;
+        let transaction =
+            ParsedTransactionLog::from_parsed_logs_with_options(parsed_logs, options)?;
+        let return_data = return_data.map(RpcReturnData::decode).transpose()?;
+
+        let mut mismatches = vec![];
+
+        if let Some(reported) = units_consumed {
+            let reconstructed = transaction.compute_units_consumed;
+            let all_logged = transaction
+                .frames
+                .iter()
+                .all(|frame| frame.compute_log.is_some());
+            if reconstructed > reported || (all_logged && reconstructed < reported) {
+                mismatches.push(RpcMismatch::UnitsConsumed {
+                    reported,
+                    reconstructed,
+                });
+            }
+        }
+
+        let reconstructed = last_return_data(&transaction.frames);
+        if reconstructed != return_data {
+            mismatches.push(RpcMismatch::ReturnData {
+                reported: return_data.clone(),
+                reconstructed,
+            });
+        }
+
+        Ok(RpcTransactionLog {
+            transaction,
+            units_consumed,
+            return_data,
+            mismatches,
+        })
+    }
+}
+
+/// The return data set last while executing the frames
+fn last_return_data(frames: &[ParsedStructuredLog]) -> Option<ReturnData> {
+    fn visit(frame: &ParsedStructuredLog, last: &mut Option<ReturnData>) {
+        for event in &frame.events {
+            match event {
+                FrameEvent::Cpi(index) => visit(&frame.cpi_logs[*index], last),
+                FrameEvent::Return => {
+                    *last = frame.return_data.clone().map(|data| ReturnData {
+                        program_id: frame.program_id,
+                        data,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut last = None;
+    for frame in frames {
+        visit(frame, &mut last);
+    }
+    last
+}
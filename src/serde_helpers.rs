@@ -0,0 +1,284 @@
+//! Serde encodings for fields without a human friendly representation.
+//!
+//! Pubkeys are encoded as base58 strings and byte payloads as base64 strings
+//! by default. The byte payloads of this crate's log types can be encoded as
+//! hex instead, by serializing or deserializing them inside [`with_encoding`]
+//! or wrapped in [`Hex`]. The [`hex`] modules always encode byte payloads as
+//! hex strings, for use in `#[serde(with = "...")]` on your own types.
+
+use std::{cell::Cell, str::FromStr};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use solana_pubkey::Pubkey;
+
+/// How byte payloads are written as strings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteEncoding {
+    #[default]
+    Base64,
+    Hex,
+}
+
+thread_local! {
+    static ENCODING: Cell<ByteEncoding> = const { Cell::new(ByteEncoding::Base64) };
+}
+
+/// Runs `f` with byte payloads encoded and decoded as `encoding`.
+///
+/// The encoding applies to serialization and deserialization running on the
+/// current thread until `f` returns, and is restored afterwards.
+pub fn with_encoding<R>(encoding: ByteEncoding, f: impl FnOnce() -> R) -> R {
+    struct Restore(ByteEncoding);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ENCODING.set(self.0);
+        }
+    }
+
+    let _restore = Restore(ENCODING.replace(encoding));
+    f()
+}
+
+/// Serializes and deserializes the wrapped value with hex byte payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex<T>(pub T);
+
+impl<T: Serialize> Serialize for Hex<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        with_encoding(ByteEncoding::Hex, || self.0.serialize(serializer))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Hex<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        with_encoding(ByteEncoding::Hex, || T::deserialize(deserializer)).map(Hex)
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    match ENCODING.get() {
+        ByteEncoding::Base64 => BASE64_STANDARD.encode(bytes),
+        ByteEncoding::Hex => encode_hex(bytes),
+    }
+}
+
+fn decode<E: Error>(encoded: &str) -> Result<Vec<u8>, E> {
+    match ENCODING.get() {
+        ByteEncoding::Base64 => BASE64_STANDARD.decode(encoded).map_err(E::custom),
+        ByteEncoding::Hex => decode_hex(encoded),
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    bytes
+        .iter()
+        .flat_map(|byte| {
+            [
+                DIGITS[usize::from(byte >> 4)],
+                DIGITS[usize::from(byte & 0xf)],
+            ]
+        })
+        .map(char::from)
+        .collect()
+}
+
+fn decode_hex<E: Error>(encoded: &str) -> Result<Vec<u8>, E> {
+    fn nibble(digit: u8) -> Option<u8> {
+        char::from(digit).to_digit(16).map(|nibble| nibble as u8)
+    }
+
+    let pairs = encoded.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(E::custom("hex string has an odd number of digits"));
+    }
+    pairs
+        .map(|pair| {
+            nibble(pair[0])
+                .zip(nibble(pair[1]))
+                .map(|(high, low)| high << 4 | low)
+                .ok_or_else(|| E::custom(format!("invalid hex string: {encoded}")))
+        })
+        .collect()
+}
+
+fn serialize_bytes<S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+    encode: fn(&[u8]) -> String,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(bytes))
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    decode: fn(&str) -> Result<Vec<u8>, D::Error>,
+) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    decode(&encoded)
+}
+
+fn serialize_option_bytes<S: Serializer>(
+    bytes: &Option<Vec<u8>>,
+    serializer: S,
+    encode: fn(&[u8]) -> String,
+) -> Result<S::Ok, S::Error> {
+    match bytes {
+        Some(bytes) => serializer.serialize_some(&encode(bytes)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_option_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    decode: fn(&str) -> Result<Vec<u8>, D::Error>,
+) -> Result<Option<Vec<u8>>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|encoded| decode(&encoded))
+        .transpose()
+}
+
+fn serialize_bytes_vec<S: Serializer>(
+    fields: &[Vec<u8>],
+    serializer: S,
+    encode: fn(&[u8]) -> String,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(fields.iter().map(|bytes| encode(bytes)))
+}
+
+fn deserialize_bytes_vec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    decode: fn(&str) -> Result<Vec<u8>, D::Error>,
+) -> Result<Vec<Vec<u8>>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|encoded| decode(encoded))
+        .collect()
+}
+
+/// A [`Pubkey`] as a base58 string
+pub mod pubkey {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Pubkey::from_str(&encoded).map_err(D::Error::custom)
+    }
+}
+
+/// A `Vec<u8>` as a string in the current [`ByteEncoding`]
+pub mod bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(bytes, serializer, encode)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        deserialize_bytes(deserializer, decode)
+    }
+}
+
+/// An `Option<Vec<u8>>` as an optional string in the current [`ByteEncoding`]
+pub mod option_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_option_bytes(bytes, serializer, encode)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        deserialize_option_bytes(deserializer, decode)
+    }
+}
+
+/// A `Vec<Vec<u8>>` as a list of strings in the current [`ByteEncoding`]
+pub mod bytes_vec {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(fields: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes_vec(fields, serializer, encode)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        deserialize_bytes_vec(deserializer, decode)
+    }
+}
+
+/// Byte payloads as hex strings, whatever the current [`ByteEncoding`]
+pub mod hex {
+    use serde::{de::Error, Deserializer, Serializer};
+
+    fn encode(bytes: &[u8]) -> String {
+        super::encode_hex(bytes)
+    }
+
+    fn decode<E: Error>(encoded: &str) -> Result<Vec<u8>, E> {
+        super::decode_hex(encoded)
+    }
+
+    /// A `Vec<u8>` as a hex string
+    pub mod bytes {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+            super::super::serialize_bytes(bytes, serializer, encode)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<u8>, D::Error> {
+            super::super::deserialize_bytes(deserializer, decode)
+        }
+    }
+
+    /// An `Option<Vec<u8>>` as an optional hex string
+    pub mod option_bytes {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            bytes: &Option<Vec<u8>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            super::super::serialize_option_bytes(bytes, serializer, encode)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Vec<u8>>, D::Error> {
+            super::super::deserialize_option_bytes(deserializer, decode)
+        }
+    }
+
+    /// A `Vec<Vec<u8>>` as a list of hex strings
+    pub mod bytes_vec {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            fields: &[Vec<u8>],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            super::super::serialize_bytes_vec(fields, serializer, encode)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Vec<u8>>, D::Error> {
+            super::super::deserialize_bytes_vec(deserializer, decode)
+        }
+    }
+}
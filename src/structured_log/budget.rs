@@ -0,0 +1,144 @@
+use super::{compute_stats::ComputeUnitStats, view::FrameView};
+
+/// The largest limit a `SetComputeUnitLimit` instruction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Recommends a `SetComputeUnitLimit` value for one kind of transaction from
+/// the units its past executions consumed.
+///
+/// A transaction's consumption is the sum of its top-level frames, and the
+/// limit it requested is estimated from the budget of its first top-level
+/// frame that logged one. Builtin programs log neither, so the few units they
+/// use are left to the safety margin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetAnalyzer {
+    /// The quantile of consumed units to cover, between `0.0` and `1.0`
+    pub quantile: f64,
+    /// The factor the covered consumption is multiplied by, at least `1.0`
+    pub margin: f64,
+    stats: ComputeUnitStats,
+}
+
+impl Default for BudgetAnalyzer {
+    fn default() -> Self {
+        Self {
+            quantile: 0.99,
+            margin: 1.1,
+            stats: ComputeUnitStats::default(),
+        }
+    }
+}
+
+/// A recommended compute unit limit and what the observed transactions wasted
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BudgetRecommendation {
+    pub unit_limit: u32,
+    pub transactions: u64,
+    /// The consumed units at the analyzer's quantile
+    pub quantile_consumed: u64,
+    pub max_consumed: u64,
+    pub total_consumed: u64,
+    pub total_requested: u64,
+    /// Requested units that went unused
+    pub total_wasted: u64,
+}
+
+impl BudgetRecommendation {
+    /// The share of requested units that went unused
+    pub fn wasted_ratio(&self) -> f64 {
+        if self.total_requested == 0 {
+            return 0.0;
+        }
+        self.total_wasted as f64 / self.total_requested as f64
+    }
+}
+
+impl BudgetAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The quantile of consumed units to cover, between `0.0` and `1.0`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quantile` is outside `0.0..=1.0` or NaN.
+    pub fn quantile(mut self, quantile: f64) -> Self {
+        check_quantile(quantile);
+        self.quantile = quantile;
+        self
+    }
+
+    /// The factor the covered consumption is multiplied by, at least `1.0`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `margin` is below `1.0` or NaN.
+    pub fn margin(mut self, margin: f64) -> Self {
+        check_margin(margin);
+        self.margin = margin;
+        self
+    }
+
+    /// Adds the top-level frames of a transaction
+    pub fn add<F: FrameView>(&mut self, frames: &[F]) {
+        let mut consumed = 0;
+        let mut requested = None;
+        for frame in frames {
+            if let Some(compute_log) = frame.compute_log() {
+                requested.get_or_insert(consumed + compute_log.budget);
+                consumed += compute_log.consumed;
+            }
+        }
+        if let Some(requested) = requested {
+            self.record(consumed, requested);
+        }
+    }
+
+    /// Adds a transaction that consumed `consumed` out of `requested` units
+    pub fn record(&mut self, consumed: u64, requested: u64) {
+        self.stats.record(consumed, requested);
+    }
+
+    pub fn merge(&mut self, other: &BudgetAnalyzer) {
+        self.stats.merge(&other.stats);
+    }
+
+    /// The recommendation, once any transaction logged its consumption
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quantile` or `margin` were set out of range, see
+    /// [`quantile`](Self::quantile) and [`margin`](Self::margin).
+    pub fn recommend(&self) -> Option<BudgetRecommendation> {
+        check_quantile(self.quantile);
+        check_margin(self.margin);
+
+        let quantile_consumed = self.stats.percentile(self.quantile)?;
+        let unit_limit = (quantile_consumed as f64 * self.margin).ceil();
+        let total_consumed = self.stats.total_consumed();
+        let total_requested = self.stats.total_budget();
+
+        Some(BudgetRecommendation {
+            unit_limit: unit_limit.min(f64::from(MAX_COMPUTE_UNIT_LIMIT)) as u32,
+            transactions: self.stats.samples(),
+            quantile_consumed,
+            max_consumed: self.stats.max()?,
+            total_consumed,
+            total_requested,
+            total_wasted: total_requested.saturating_sub(total_consumed),
+        })
+    }
+}
+
+fn check_quantile(quantile: f64) {
+    assert!(
+        (0.0..=1.0).contains(&quantile),
+        "quantile must be between 0.0 and 1.0, got {quantile}"
+    );
+}
+
+fn check_margin(margin: f64) {
+    assert!(margin >= 1.0, "margin must be at least 1.0, got {margin}");
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Write},
+};
+
+use super::{
+    graph::known_program_name,
+    view::{FrameResult, FrameView},
+    write_json_str,
+};
+
+/// Aggregated invocations of a program by one caller
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallStats {
+    pub calls: u64,
+    /// The units consumed by the callee, including its own CPIs
+    pub consumed: u64,
+    pub failures: u64,
+}
+
+impl CallStats {
+    fn add<F: FrameView>(&mut self, frame: &F) {
+        self.calls += 1;
+        self.consumed += frame.inclusive_consumed().unwrap_or(0);
+        if matches!(frame.result(), FrameResult::Err(_)) {
+            self.failures += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &CallStats) {
+        self.calls += other.calls;
+        self.consumed += other.consumed;
+        self.failures += other.failures;
+    }
+}
+
+/// Counts caller to callee invocations across any number of call trees.
+///
+/// Top-level frames are counted as roots, invoked directly by a transaction.
+/// Graphs built from separate shards of traffic can be combined with
+/// [`CallGraph::merge`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    roots: BTreeMap<String, CallStats>,
+    edges: BTreeMap<(String, String), CallStats>,
+}
+
+impl CallGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a top-level frame and all of its CPIs
+    pub fn add<F: FrameView>(&mut self, frame: &F) {
+        let program_id = frame.program_id();
+        self.roots.entry(program_id.clone()).or_default().add(frame);
+        self.add_cpis(&program_id, frame);
+    }
+
+    pub fn add_all<'a, F: FrameView + 'a>(&mut self, frames: impl IntoIterator<Item = &'a F>) {
+        for frame in frames {
+            self.add(frame);
+        }
+    }
+
+    pub fn merge(&mut self, other: &CallGraph) {
+        for (program_id, stats) in &other.roots {
+            self.roots
+                .entry(program_id.clone())
+                .or_default()
+                .merge(stats);
+        }
+        for (edge, stats) in &other.edges {
+            self.edges.entry(edge.clone()).or_default().merge(stats);
+        }
+    }
+
+    /// The programs invoked as top-level instructions
+    pub fn roots(&self) -> impl Iterator<Item = (&str, &CallStats)> {
+        self.roots
+            .iter()
+            .map(|(program_id, stats)| (program_id.as_str(), stats))
+    }
+
+    /// Every caller to callee edge, ordered by caller then callee
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str, &CallStats)> {
+        self.edges
+            .iter()
+            .map(|((caller, callee), stats)| (caller.as_str(), callee.as_str(), stats))
+    }
+
+    pub fn edge(&self, caller: &str, callee: &str) -> Option<&CallStats> {
+        self.edges.get(&(caller.to_string(), callee.to_string()))
+    }
+
+    /// Wraps the graph so it renders as Graphviz DOT
+    pub fn dot(&self) -> impl Display + '_ {
+        DisplayCallGraph {
+            graph: self,
+            json: false,
+        }
+    }
+
+    /// Wraps the graph so it renders as JSON
+    pub fn json(&self) -> impl Display + '_ {
+        DisplayCallGraph {
+            graph: self,
+            json: true,
+        }
+    }
+
+    pub fn write_dot(&self, f: &mut impl Write) -> fmt::Result {
+        let mut programs: Vec<_> = self.roots.keys().collect();
+        for (caller, callee) in self.edges.keys() {
+            programs.push(caller);
+            programs.push(callee);
+        }
+        programs.sort();
+        programs.dedup();
+
+        writeln!(f, "digraph calls {{")?;
+        writeln!(f, "    node [shape=box];")?;
+        writeln!(f, "    transaction [shape=ellipse];")?;
+        for program_id in programs {
+            let label = known_program_name(program_id).unwrap_or(program_id);
+            writeln!(f, "    \"{program_id}\" [label=\"{label}\"];")?;
+        }
+        for (program_id, stats) in &self.roots {
+            write!(f, "    transaction -> \"{program_id}\"")?;
+            write_dot_stats(f, stats)?;
+        }
+        for ((caller, callee), stats) in &self.edges {
+            write!(f, "    \"{caller}\" -> \"{callee}\"")?;
+            write_dot_stats(f, stats)?;
+        }
+        write!(f, "}}")
+    }
+
+    pub fn write_json(&self, f: &mut impl Write) -> fmt::Result {
+        f.write_str("{\"roots\":[")?;
+        for (position, (program_id, stats)) in self.roots.iter().enumerate() {
+            if position > 0 {
+                f.write_char(',')?;
+            }
+            f.write_str("{\"program_id\":")?;
+            write_json_str(f, program_id)?;
+            write_json_stats(f, stats)?;
+        }
+        f.write_str("],\"edges\":[")?;
+        for (position, ((caller, callee), stats)) in self.edges.iter().enumerate() {
+            if position > 0 {
+                f.write_char(',')?;
+            }
+            f.write_str("{\"caller\":")?;
+            write_json_str(f, caller)?;
+            f.write_str(",\"callee\":")?;
+            write_json_str(f, callee)?;
+            write_json_stats(f, stats)?;
+        }
+        f.write_str("]}")
+    }
+
+    fn add_cpis<F: FrameView>(&mut self, caller: &str, frame: &F) {
+        for cpi_log in frame.cpi_logs() {
+            let callee = cpi_log.program_id();
+            self.edges
+                .entry((caller.to_string(), callee.clone()))
+                .or_default()
+                .add(cpi_log);
+            self.add_cpis(&callee, cpi_log);
+        }
+    }
+}
+
+fn write_dot_stats(f: &mut impl Write, stats: &CallStats) -> fmt::Result {
+    write!(f, " [label=\"{} calls\\n{} CU", stats.calls, stats.consumed)?;
+    if stats.failures > 0 {
+        write!(f, "\\n{} failed\", color=red", stats.failures)?;
+    } else {
+        f.write_char('"')?;
+    }
+    writeln!(f, "];")
+}
+
+fn write_json_stats(f: &mut impl Write, stats: &CallStats) -> fmt::Result {
+    write!(
+        f,
+        ",\"calls\":{},\"consumed\":{},\"failures\":{}}}",
+        stats.calls, stats.consumed, stats.failures
+    )
+}
+
+struct DisplayCallGraph<'a> {
+    graph: &'a CallGraph,
+    json: bool,
+}
+
+impl Display for DisplayCallGraph<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.json {
+            self.graph.write_json(f)
+        } else {
+            self.graph.write_dot(f)
+        }
+    }
+}
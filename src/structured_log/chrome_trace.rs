@@ -0,0 +1,208 @@
+use std::fmt::{self, Display, Write};
+
+use super::{
+    view::{FrameResult, FrameView},
+    write_json_str, FrameEvent,
+};
+
+/// Exports call trees as Chrome Trace Event JSON, viewable in
+/// `chrome://tracing` or Perfetto.
+///
+/// Every frame becomes a span lasting one microsecond per consumed compute
+/// unit, and program logs become instant events. A CPI starts where its
+/// parent had used up the difference between their budgets, so gaps between
+/// spans are units the caller spent itself. Each added transaction gets its
+/// own track starting at zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChromeTrace {
+    events: Vec<TraceEvent>,
+    transactions: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TraceEvent {
+    Span {
+        tid: u64,
+        name: String,
+        ts: u64,
+        dur: u64,
+        args: Vec<(&'static str, Arg)>,
+    },
+    Instant {
+        tid: u64,
+        name: String,
+        ts: u64,
+    },
+    ThreadName {
+        tid: u64,
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Arg {
+    Str(String),
+    Num(u64),
+}
+
+impl ChromeTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the top-level frames of a transaction on a new track
+    pub fn add<F: FrameView>(&mut self, frames: &[F]) {
+        self.add_named(&format!("transaction {}", self.transactions), frames);
+    }
+
+    /// Adds the top-level frames of a transaction on a new track labeled `name`
+    pub fn add_named<F: FrameView>(&mut self, name: &str, frames: &[F]) {
+        let tid = self.transactions;
+        self.transactions = tid + 1;
+        self.events.push(TraceEvent::ThreadName {
+            tid,
+            name: name.to_string(),
+        });
+
+        let mut cursor = 0;
+        for frame in frames {
+            cursor = self.add_frame(tid, frame, cursor);
+        }
+    }
+
+    /// Adds a frame starting at `start` and returns when it ended
+    fn add_frame<F: FrameView>(&mut self, tid: u64, frame: &F, start: u64) -> u64 {
+        let budget = frame.compute_log().map(|compute_log| compute_log.budget);
+        let mut cursor = start;
+
+        let span = self.events.len();
+        self.events.push(TraceEvent::Span {
+            tid,
+            name: String::new(),
+            ts: start,
+            dur: 0,
+            args: vec![],
+        });
+
+        for event in frame.events() {
+            match event {
+                FrameEvent::Log(index) => self.events.push(TraceEvent::Instant {
+                    tid,
+                    name: frame.program_log(*index).to_string(),
+                    ts: cursor,
+                }),
+                FrameEvent::Checkpoint(index) => {
+                    if let Some(budget) = budget {
+                        let used = budget.saturating_sub(frame.compute_checkpoints()[*index]);
+                        cursor = cursor.max(start + used);
+                    }
+                }
+                FrameEvent::Cpi(index) => {
+                    let cpi_log = &frame.cpi_logs()[*index];
+                    let child_budget = cpi_log.compute_log().map(|compute_log| compute_log.budget);
+                    let child_start = match (budget, child_budget) {
+                        (Some(budget), Some(child_budget)) => {
+                            cursor.max(start + budget.saturating_sub(child_budget))
+                        }
+                        _ => cursor,
+                    };
+                    cursor = self.add_frame(tid, cpi_log, child_start);
+                }
+                _ => {}
+            }
+        }
+
+        let end = match frame.inclusive_consumed() {
+            Some(consumed) => cursor.max(start + consumed),
+            None => cursor,
+        };
+
+        let mut name = frame.program_id();
+        if let Some(instruction_name) = frame.instruction_name() {
+            name = format!("{name} ({instruction_name})");
+        }
+        let mut args = vec![("program_id", Arg::Str(frame.program_id()))];
+        if let Some(instruction_name) = frame.instruction_name() {
+            args.push(("instruction", Arg::Str(instruction_name.to_string())));
+        }
+        args.push((
+            "result",
+            Arg::Str(match frame.result() {
+                FrameResult::Success => "success".to_string(),
+                FrameResult::Err(err) => format!("failed: {err}"),
+                FrameResult::Incomplete => "incomplete".to_string(),
+            }),
+        ));
+        if let Some(compute_log) = frame.compute_log() {
+            args.push(("consumed", Arg::Num(compute_log.consumed)));
+            args.push(("budget", Arg::Num(compute_log.budget)));
+        }
+        if let Some(self_consumed) = frame.self_consumed() {
+            args.push(("self_consumed", Arg::Num(self_consumed)));
+        }
+
+        self.events[span] = TraceEvent::Span {
+            tid,
+            name,
+            ts: start,
+            dur: end - start,
+            args,
+        };
+        end
+    }
+}
+
+impl Display for ChromeTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{\"traceEvents\":[")?;
+        for (position, event) in self.events.iter().enumerate() {
+            if position > 0 {
+                f.write_char(',')?;
+            }
+            match event {
+                TraceEvent::Span {
+                    tid,
+                    name,
+                    ts,
+                    dur,
+                    args,
+                } => {
+                    f.write_str("{\"name\":")?;
+                    write_json_str(f, name)?;
+                    write!(
+                        f,
+                        ",\"cat\":\"frame\",\"ph\":\"X\",\"ts\":{ts},\"dur\":{dur},\"pid\":1,\"tid\":{tid},\"args\":{{"
+                    )?;
+                    for (position, (key, value)) in args.iter().enumerate() {
+                        if position > 0 {
+                            f.write_char(',')?;
+                        }
+                        write!(f, "\"{key}\":")?;
+                        match value {
+                            Arg::Str(value) => write_json_str(f, value)?,
+                            Arg::Num(value) => write!(f, "{value}")?,
+                        }
+                    }
+                    f.write_str("}}")?;
+                }
+                TraceEvent::Instant { tid, name, ts } => {
+                    f.write_str("{\"name\":")?;
+                    write_json_str(f, name)?;
+                    write!(
+                        f,
+                        ",\"cat\":\"log\",\"ph\":\"i\",\"s\":\"t\",\"ts\":{ts},\"pid\":1,\"tid\":{tid}}}"
+                    )?;
+                }
+                TraceEvent::ThreadName { tid, name } => {
+                    write!(
+                        f,
+                        "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{tid},\"args\":{{\"name\":"
+                    )?;
+                    write_json_str(f, name)?;
+                    f.write_str("}}")?;
+                }
+            }
+        }
+        f.write_str("]}")
+    }
+}
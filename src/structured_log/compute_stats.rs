@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use super::view::{FrameResult, FrameView};
+
+/// Values below `2^SUB_BUCKET_BITS` get a bucket each, larger values share
+/// `2^SUB_BUCKET_BITS` buckets per power of two, bounding the relative error
+/// of a percentile to under 1%.
+const SUB_BUCKET_BITS: u32 = 7;
+
+/// Compute unit statistics of one program or instruction
+///
+/// Percentiles are read from a log-linear histogram, so they are exact below
+/// 128 units and within 1% above, while any two stats can be merged exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComputeUnitStats {
+    invocations: u64,
+    failures: u64,
+    samples: u64,
+    min: u64,
+    max: u64,
+    total_consumed: u64,
+    total_budget: u64,
+    histogram: BTreeMap<u32, u64>,
+}
+
+impl ComputeUnitStats {
+    /// Records a frame, without its CPIs
+    pub fn add<F: FrameView>(&mut self, frame: &F) {
+        self.invocations += 1;
+        if matches!(frame.result(), FrameResult::Err(_)) {
+            self.failures += 1;
+        }
+        if let Some(compute_log) = frame.compute_log() {
+            self.record(compute_log.consumed, compute_log.budget);
+        }
+    }
+
+    /// Records a single sample of consumed units out of a budget
+    pub fn record(&mut self, consumed: u64, budget: u64) {
+        if self.samples == 0 || consumed < self.min {
+            self.min = consumed;
+        }
+        self.max = self.max.max(consumed);
+        self.samples += 1;
+        self.total_consumed += consumed;
+        self.total_budget += budget;
+        *self.histogram.entry(bucket(consumed)).or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: &ComputeUnitStats) {
+        if other.samples > 0 && (self.samples == 0 || other.min < self.min) {
+            self.min = other.min;
+        }
+        self.max = self.max.max(other.max);
+        self.invocations += other.invocations;
+        self.failures += other.failures;
+        self.samples += other.samples;
+        self.total_consumed += other.total_consumed;
+        self.total_budget += other.total_budget;
+        for (bucket, count) in &other.histogram {
+            *self.histogram.entry(*bucket).or_default() += count;
+        }
+    }
+
+    /// The number of recorded frames
+    pub fn invocations(&self) -> u64 {
+        self.invocations
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.failures
+    }
+
+    /// The share of recorded frames that failed
+    pub fn failure_rate(&self) -> f64 {
+        if self.invocations == 0 {
+            return 0.0;
+        }
+        self.failures as f64 / self.invocations as f64
+    }
+
+    /// The number of recorded frames that logged their consumed units
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        (self.samples > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        (self.samples > 0).then_some(self.max)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.total_consumed as f64 / self.samples as f64)
+    }
+
+    pub fn total_consumed(&self) -> u64 {
+        self.total_consumed
+    }
+
+    pub fn total_budget(&self) -> u64 {
+        self.total_budget
+    }
+
+    /// The share of the available budget that was consumed
+    pub fn utilization(&self) -> Option<f64> {
+        (self.total_budget > 0).then(|| self.total_consumed as f64 / self.total_budget as f64)
+    }
+
+    /// The consumed units at `quantile`, between `0.0` and `1.0`
+    pub fn percentile(&self, quantile: f64) -> Option<u64> {
+        if self.samples == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.samples as f64).ceil() as u64).max(1);
+
+        let mut seen = 0;
+        for (bucket, count) in &self.histogram {
+            seen += count;
+            if seen >= rank {
+                return Some(bucket_high(*bucket).clamp(self.min, self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    pub fn p50(&self) -> Option<u64> {
+        self.percentile(0.5)
+    }
+
+    pub fn p90(&self) -> Option<u64> {
+        self.percentile(0.9)
+    }
+
+    pub fn p99(&self) -> Option<u64> {
+        self.percentile(0.99)
+    }
+}
+
+/// Collects compute unit statistics per program, and per instruction name
+/// where one was logged, from any number of call trees.
+///
+/// Every frame is recorded, including CPIs. Collectors fed by separate shards
+/// can be combined with [`ComputeStats::merge`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComputeStats {
+    programs: BTreeMap<String, ComputeUnitStats>,
+    instructions: BTreeMap<String, BTreeMap<String, ComputeUnitStats>>,
+}
+
+impl ComputeStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a top-level frame and all of its CPIs
+    pub fn add<F: FrameView>(&mut self, frame: &F) {
+        let program_id = frame.program_id();
+        if let Some(name) = frame.instruction_name() {
+            self.instructions
+                .entry(program_id.clone())
+                .or_default()
+                .entry(name.to_string())
+                .or_default()
+                .add(frame);
+        }
+        self.programs.entry(program_id).or_default().add(frame);
+
+        for cpi_log in frame.cpi_logs() {
+            self.add(cpi_log);
+        }
+    }
+
+    pub fn add_all<'a, F: FrameView + 'a>(&mut self, frames: impl IntoIterator<Item = &'a F>) {
+        for frame in frames {
+            self.add(frame);
+        }
+    }
+
+    pub fn merge(&mut self, other: &ComputeStats) {
+        for (program_id, stats) in &other.programs {
+            self.programs
+                .entry(program_id.clone())
+                .or_default()
+                .merge(stats);
+        }
+        for (program_id, instructions) in &other.instructions {
+            let merged = self.instructions.entry(program_id.clone()).or_default();
+            for (name, stats) in instructions {
+                merged.entry(name.clone()).or_default().merge(stats);
+            }
+        }
+    }
+
+    pub fn program(&self, program_id: &str) -> Option<&ComputeUnitStats> {
+        self.programs.get(program_id)
+    }
+
+    pub fn instruction(&self, program_id: &str, name: &str) -> Option<&ComputeUnitStats> {
+        self.instructions.get(program_id)?.get(name)
+    }
+
+    pub fn programs(&self) -> impl Iterator<Item = (&str, &ComputeUnitStats)> {
+        self.programs
+            .iter()
+            .map(|(program_id, stats)| (program_id.as_str(), stats))
+    }
+
+    /// The stats of every named instruction, keyed by program id and name
+    pub fn instructions(&self) -> impl Iterator<Item = (&str, &str, &ComputeUnitStats)> {
+        self.instructions
+            .iter()
+            .flat_map(|(program_id, instructions)| {
+                instructions
+                    .iter()
+                    .map(move |(name, stats)| (program_id.as_str(), name.as_str(), stats))
+            })
+    }
+}
+
+fn bucket(value: u64) -> u32 {
+    if value < 1 << SUB_BUCKET_BITS {
+        return value as u32;
+    }
+    let shift = 63 - value.leading_zeros() - SUB_BUCKET_BITS;
+    let sub_bucket = (value >> shift) as u32 - (1 << SUB_BUCKET_BITS);
+    ((shift + 1) << SUB_BUCKET_BITS) + sub_bucket
+}
+
+/// The largest value that falls into `bucket`
+fn bucket_high(bucket: u32) -> u64 {
+    if bucket < 1 << SUB_BUCKET_BITS {
+        return u64::from(bucket);
+    }
+    let shift = (bucket >> SUB_BUCKET_BITS) - 1;
+    let low = u64::from((bucket & ((1 << SUB_BUCKET_BITS) - 1)) + (1 << SUB_BUCKET_BITS)) << shift;
+    low + ((1 << shift) - 1)
+}
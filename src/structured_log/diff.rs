@@ -0,0 +1,245 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use super::view::FrameView;
+
+/// The compute usage of every frame in a set of transactions, keyed by the
+/// frame's path in the call tree.
+///
+/// A path joins the labels of a frame and its callers with `/`, where a label
+/// is the program id followed by the instruction name, if one was logged.
+/// Repeated labels under the same caller are told apart by a `#<n>` suffix,
+/// so the same transactions profiled twice produce the same paths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComputeProfile {
+    frames: BTreeMap<String, FrameUsage>,
+}
+
+/// The aggregated usage of all frames sharing a path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameUsage {
+    pub calls: u64,
+    /// The number of calls that logged their consumed units
+    pub samples: u64,
+    pub total_consumed: u64,
+}
+
+impl FrameUsage {
+    pub fn mean_consumed(&self) -> Option<u64> {
+        (self.samples > 0).then(|| self.total_consumed / self.samples)
+    }
+
+    fn merge(&mut self, other: &FrameUsage) {
+        self.calls += other.calls;
+        self.samples += other.samples;
+        self.total_consumed += other.total_consumed;
+    }
+}
+
+impl ComputeProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the top-level frames of a transaction
+    pub fn add<F: FrameView>(&mut self, frames: &[F]) {
+        self.add_frames(frames, "");
+    }
+
+    pub fn merge(&mut self, other: &ComputeProfile) {
+        for (path, usage) in &other.frames {
+            self.frames.entry(path.clone()).or_default().merge(usage);
+        }
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = (&str, &FrameUsage)> {
+        self.frames
+            .iter()
+            .map(|(path, usage)| (path.as_str(), usage))
+    }
+
+    pub fn frame(&self, path: &str) -> Option<&FrameUsage> {
+        self.frames.get(path)
+    }
+
+    fn add_frames<F: FrameView>(&mut self, frames: &[F], parent: &str) {
+        let mut occurrences = BTreeMap::<String, usize>::new();
+        for frame in frames {
+            let mut label = frame.program_id();
+            if let Some(name) = frame.instruction_name() {
+                label = format!("{label} ({name})");
+            }
+            let occurrence = occurrences.entry(label.clone()).or_default();
+            if *occurrence > 0 {
+                label = format!("{label}#{occurrence}");
+            }
+            *occurrence += 1;
+
+            let path = if parent.is_empty() {
+                label
+            } else {
+                format!("{parent}/{label}")
+            };
+
+            let usage = self.frames.entry(path.clone()).or_default();
+            usage.calls += 1;
+            if let Some(consumed) = frame.inclusive_consumed() {
+                usage.samples += 1;
+                usage.total_consumed += consumed;
+            }
+            self.add_frames(frame.cpi_logs(), &path);
+        }
+    }
+}
+
+/// The change in compute usage of a single path between two profiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameDiff {
+    pub path: String,
+    /// The usage in the baseline, `None` when the frame is new
+    pub baseline: Option<FrameUsage>,
+    /// The usage in the candidate, `None` when the frame was removed
+    pub candidate: Option<FrameUsage>,
+}
+
+impl FrameDiff {
+    /// The change in mean consumed units, when both sides logged them
+    pub fn delta(&self) -> Option<i64> {
+        let baseline = self.baseline?.mean_consumed()?;
+        let candidate = self.candidate?.mean_consumed()?;
+        Some(candidate as i64 - baseline as i64)
+    }
+
+    /// The change in mean consumed units relative to the baseline
+    pub fn ratio(&self) -> Option<f64> {
+        let baseline = self.baseline?.mean_consumed()?;
+        (baseline > 0).then(|| self.delta().unwrap_or(0) as f64 / baseline as f64)
+    }
+}
+
+/// When an increase in consumed units counts as a regression
+///
+/// A frame regresses when its mean consumption grew by more than both
+/// `units` and `percent`, so small frames do not fail on noise.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RegressionThreshold {
+    pub units: u64,
+    pub percent: f64,
+}
+
+impl RegressionThreshold {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn units(mut self, units: u64) -> Self {
+        self.units = units;
+        self
+    }
+
+    pub fn percent(mut self, percent: f64) -> Self {
+        self.percent = percent;
+        self
+    }
+
+    pub fn exceeded_by(&self, frame: &FrameDiff) -> bool {
+        let Some(delta) = frame.delta() else {
+            return false;
+        };
+        delta > 0
+            && delta.unsigned_abs() > self.units
+            && frame
+                .ratio()
+                .is_none_or(|ratio| ratio * 100.0 > self.percent)
+    }
+}
+
+/// The differences in compute usage between a baseline and a candidate profile
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComputeDiff {
+    /// Every path that changed, was added or was removed, ordered by path
+    pub frames: Vec<FrameDiff>,
+}
+
+impl ComputeDiff {
+    pub fn new(baseline: &ComputeProfile, candidate: &ComputeProfile) -> Self {
+        let mut paths: Vec<_> = baseline
+            .frames
+            .keys()
+            .chain(candidate.frames.keys())
+            .collect();
+        paths.sort();
+        paths.dedup();
+
+        let frames = paths
+            .into_iter()
+            .map(|path| FrameDiff {
+                path: path.clone(),
+                baseline: baseline.frames.get(path).copied(),
+                candidate: candidate.frames.get(path).copied(),
+            })
+            .filter(|frame| {
+                frame.baseline.is_none()
+                    || frame.candidate.is_none()
+                    || frame.delta().is_some_and(|delta| delta != 0)
+            })
+            .collect();
+        Self { frames }
+    }
+
+    /// Frames only present in the candidate
+    pub fn added(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.frames.iter().filter(|frame| frame.baseline.is_none())
+    }
+
+    /// Frames only present in the baseline
+    pub fn removed(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.frames.iter().filter(|frame| frame.candidate.is_none())
+    }
+
+    /// Frames whose consumption grew past `threshold`
+    pub fn regressions<'a>(
+        &'a self,
+        threshold: &'a RegressionThreshold,
+    ) -> impl Iterator<Item = &'a FrameDiff> {
+        self.frames
+            .iter()
+            .filter(|frame| threshold.exceeded_by(frame))
+    }
+}
+
+impl Display for ComputeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let consumed =
+            |usage: Option<FrameUsage>| match usage.and_then(|usage| usage.mean_consumed()) {
+                Some(consumed) => consumed.to_string(),
+                None => "-".to_string(),
+            };
+
+        for frame in &self.frames {
+            write!(f, "{}: ", frame.path)?;
+            match (frame.baseline, frame.candidate) {
+                (None, candidate) => write!(f, "added ({} CU)", consumed(candidate))?,
+                (baseline, None) => write!(f, "removed ({} CU)", consumed(baseline))?,
+                (baseline, candidate) => {
+                    write!(f, "{} -> {} CU", consumed(baseline), consumed(candidate))?;
+                    if let Some(delta) = frame.delta() {
+                        write!(f, " ({delta:+}")?;
+                        if let Some(ratio) = frame.ratio() {
+                            write!(f, ", {:+.1}%", ratio * 100.0)?;
+                        }
+                        write!(f, ")")?;
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
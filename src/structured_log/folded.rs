@@ -0,0 +1,83 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use super::view::FrameView;
+
+/// Aggregates the compute usage of call trees into the folded stack format
+/// read by `inferno` and `flamegraph.pl`.
+///
+/// ```text
+/// D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns 7355
+/// D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns;TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA 4645
+/// ```
+///
+/// Each stack is weighted by the frame's `self_consumed` units and identical
+/// stacks are summed, so any number of transactions can be added. Frames that
+/// logged no consumption are left out, as their units are already counted in
+/// their caller.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldedStacks {
+    pub instruction_names: bool,
+    stacks: BTreeMap<String, u64>,
+}
+
+impl FoldedStacks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Label frames with their instruction name, e.g. `<program id> (Transfer)`
+    pub fn instruction_names(mut self, show: bool) -> Self {
+        self.instruction_names = show;
+        self
+    }
+
+    /// Adds a top-level frame and all of its CPIs
+    pub fn add<F: FrameView>(&mut self, frame: &F) {
+        self.add_frame(frame, "");
+    }
+
+    pub fn add_all<'a, F: FrameView + 'a>(&mut self, frames: impl IntoIterator<Item = &'a F>) {
+        for frame in frames {
+            self.add(frame);
+        }
+    }
+
+    /// The summed units of each stack, keyed by the `;` separated stack
+    pub fn stacks(&self) -> &BTreeMap<String, u64> {
+        &self.stacks
+    }
+
+    fn add_frame<F: FrameView>(&mut self, frame: &F, parent: &str) {
+        let mut label = frame.program_id();
+        if let Some(name) = frame.instruction_name().filter(|_| self.instruction_names) {
+            label = format!("{label} ({name})");
+        }
+        // `;` separates frames, so it can not appear inside of one
+        let label = label.replace(';', ":");
+
+        let stack = if parent.is_empty() {
+            label
+        } else {
+            format!("{parent};{label}")
+        };
+
+        if let Some(consumed) = frame.self_consumed().filter(|consumed| *consumed > 0) {
+            *self.stacks.entry(stack.clone()).or_default() += consumed;
+        }
+        for cpi_log in frame.cpi_logs() {
+            self.add_frame(cpi_log, &stack);
+        }
+    }
+}
+
+impl Display for FoldedStacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stack, consumed) in &self.stacks {
+            writeln!(f, "{stack} {consumed}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::{self, Display, Write};
+
+use super::view::{FrameResult, FrameView};
+
+/// Renders the call tree of a transaction as a Graphviz DOT or Mermaid diagram.
+///
+/// Each frame becomes a node labeled with its program, instruction name,
+/// consumed compute units and result, with an edge from every caller to its
+/// CPIs. Failed frames are filled red and incomplete ones are dashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphFormatter {
+    pub known_names: bool,
+    pub compute_units: bool,
+}
+
+impl Default for GraphFormatter {
+    fn default() -> Self {
+        Self {
+            known_names: true,
+            compute_units: true,
+        }
+    }
+}
+
+impl GraphFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Label well-known programs by name instead of by program id
+    pub fn known_names(mut self, show: bool) -> Self {
+        self.known_names = show;
+        self
+    }
+
+    /// Show consumed compute units
+    pub fn compute_units(mut self, show: bool) -> Self {
+        self.compute_units = show;
+        self
+    }
+
+    /// Wraps the top-level frames of a transaction so they render as DOT
+    pub fn dot<'a, F: FrameView>(&'a self, frames: &'a [F]) -> impl Display + 'a {
+        DisplayGraph {
+            formatter: self,
+            frames,
+            syntax: Syntax::Dot,
+        }
+    }
+
+    /// Wraps the top-level frames of a transaction so they render as Mermaid
+    pub fn mermaid<'a, F: FrameView>(&'a self, frames: &'a [F]) -> impl Display + 'a {
+        DisplayGraph {
+            formatter: self,
+            frames,
+            syntax: Syntax::Mermaid,
+        }
+    }
+
+    pub fn write_dot<F: FrameView>(&self, f: &mut impl Write, frames: &[F]) -> fmt::Result {
+        let (nodes, edges) = self.collect(frames);
+
+        writeln!(f, "digraph cpi {{")?;
+        writeln!(f, "    node [shape=box];")?;
+        for (id, node) in nodes.iter().enumerate() {
+            write!(f, "    n{id} [label=\"")?;
+            for (position, line) in node.lines.iter().enumerate() {
+                if position > 0 {
+                    f.write_str("\\n")?;
+                }
+                for c in line.chars() {
+                    match c {
+                        '"' | '\\' => write!(f, "\\{c}")?,
+                        '\n' => f.write_str("\\n")?,
+                        c => f.write_char(c)?,
+                    }
+                }
+            }
+            f.write_char('"')?;
+            match node.status {
+                Status::Success => {}
+                Status::Failed => {
+                    f.write_str(", style=filled, fillcolor=\"#f8d7da\", color=red")?
+                }
+                Status::Incomplete => f.write_str(", style=dashed")?,
+            }
+            writeln!(f, "];")?;
+        }
+        for (caller, callee) in edges {
+            writeln!(f, "    n{caller} -> n{callee};")?;
+        }
+        write!(f, "}}")
+    }
+
+    pub fn write_mermaid<F: FrameView>(&self, f: &mut impl Write, frames: &[F]) -> fmt::Result {
+        let (nodes, edges) = self.collect(frames);
+
+        write!(f, "flowchart TD")?;
+        for (id, node) in nodes.iter().enumerate() {
+            write!(f, "\n    n{id}[\"")?;
+            for (position, line) in node.lines.iter().enumerate() {
+                if position > 0 {
+                    f.write_str("<br/>")?;
+                }
+                for c in line.chars() {
+                    match c {
+                        '"' => f.write_str("#quot;")?,
+                        '\n' => f.write_str("<br/>")?,
+                        c => f.write_char(c)?,
+                    }
+                }
+            }
+            f.write_str("\"]")?;
+        }
+        for (caller, callee) in edges {
+            write!(f, "\n    n{caller} --> n{callee}")?;
+        }
+
+        for (status, class, style) in [
+            (Status::Failed, "failed", "fill:#f8d7da,stroke:#dc3545"),
+            (Status::Incomplete, "incomplete", "stroke-dasharray:5 5"),
+        ] {
+            let ids: Vec<_> = nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| node.status == status)
+                .map(|(id, _)| format!("n{id}"))
+                .collect();
+            if !ids.is_empty() {
+                write!(f, "\n    classDef {class} {style}")?;
+                write!(f, "\n    class {} {class}", ids.join(","))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn collect<F: FrameView>(&self, frames: &[F]) -> (Vec<Node>, Vec<(usize, usize)>) {
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for frame in frames {
+            self.collect_frame(frame, None, &mut nodes, &mut edges);
+        }
+        (nodes, edges)
+    }
+
+    fn collect_frame<F: FrameView>(
+        &self,
+        frame: &F,
+        caller: Option<usize>,
+        nodes: &mut Vec<Node>,
+        edges: &mut Vec<(usize, usize)>,
+    ) {
+        let program_id = frame.program_id();
+        let mut lines = vec![
+            match known_program_name(&program_id).filter(|_| self.known_names) {
+                Some(name) => name.to_string(),
+                None => program_id,
+            },
+        ];
+        if let Some(name) = frame.instruction_name() {
+            lines.push(name.to_string());
+        }
+        if self.compute_units {
+            if let Some(compute) = frame.compute_log() {
+                lines.push(format!("{} CU", compute.consumed));
+            }
+        }
+        let status = match frame.result() {
+            FrameResult::Success => {
+                lines.push("success".to_string());
+                Status::Success
+            }
+            FrameResult::Err(err) => {
+                lines.push(format!("failed: {err}"));
+                Status::Failed
+            }
+            FrameResult::Incomplete => {
+                lines.push("incomplete".to_string());
+                Status::Incomplete
+            }
+        };
+
+        let id = nodes.len();
+        nodes.push(Node { lines, status });
+        if let Some(caller) = caller {
+            edges.push((caller, id));
+        }
+        for cpi_log in frame.cpi_logs() {
+            self.collect_frame(cpi_log, Some(id), nodes, edges);
+        }
+    }
+}
+
+/// The name of a well-known builtin or SPL program
+pub fn known_program_name(program_id: &str) -> Option<&'static str> {
+    Some(match program_id {
+        "11111111111111111111111111111111" => "System Program",
+        "ComputeBudget111111111111111111111111111111" => "Compute Budget Program",
+        "AddressLookupTab1e1111111111111111111111111" => "Address Lookup Table Program",
+        "Stake11111111111111111111111111111111111111" => "Stake Program",
+        "Vote111111111111111111111111111111111111111" => "Vote Program",
+        "Config1111111111111111111111111111111111111" => "Config Program",
+        "BPFLoader2111111111111111111111111111111111" => "BPF Loader",
+        "BPFLoaderUpgradeab1e11111111111111111111111" => "BPF Upgradeable Loader",
+        "Ed25519SigVerify111111111111111111111111111" => "Ed25519 SigVerify Precompile",
+        "KeccakSecp256k11111111111111111111111111111" => "Secp256k1 SigVerify Precompile",
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => "Token Program",
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb" => "Token-2022 Program",
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => "Associated Token Account Program",
+        "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr" => "Memo Program",
+        "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo" => "Memo Program v1",
+        "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s" => "Token Metadata Program",
+        _ => return None,
+    })
+}
+
+struct Node {
+    lines: Vec<String>,
+    status: Status,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Success,
+    Failed,
+    Incomplete,
+}
+
+#[derive(Clone, Copy)]
+enum Syntax {
+    Dot,
+    Mermaid,
+}
+
+struct DisplayGraph<'a, F> {
+    formatter: &'a GraphFormatter,
+    frames: &'a [F],
+    syntax: Syntax,
+}
+
+impl<F: FrameView> Display for DisplayGraph<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.syntax {
+            Syntax::Dot => self.formatter.write_dot(f, self.frames),
+            Syntax::Mermaid => self.formatter.write_mermaid(f, self.frames),
+        }
+    }
+}
@@ -1,21 +1,38 @@
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug, Display, Write};
 
 use crate::{
     parsed_log::{
-        ParsedCuLog, ParsedDataLog, ParsedFailedLog, ParsedInvokeLog, ParsedLog, ParsedOtherLog,
-        ParsedProgramLog, ParsedReturnLog, ParsedSuccessLog,
+        ParsedConsumptionLog, ParsedCuLog, ParsedDataLog, ParsedFailedLog, ParsedInvokeLog,
+        ParsedLog, ParsedOtherLog, ParsedProgramLog, ParsedReturnLog, ParsedSuccessLog,
+        ParsedTruncatedLog,
     },
     raw_log::{
-        RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawLog, RawOtherLog, RawProgramLog,
-        RawReturnLog, RawSuccessLog,
+        RawConsumptionLog, RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawLog, RawOtherLog,
+        RawProgramLog, RawReturnLog, RawSuccessLog, RawTruncatedLog,
     },
-    Result,
+    LogParseError, Result,
 };
 
+use options::{StructuredParseMode, StructuredParseOptions};
+use view::{FrameResult, FrameView};
+use warning::StructuredParseWarning;
+
+pub mod budget;
+pub mod call_graph;
+pub mod chrome_trace;
+pub mod compute_stats;
+pub mod diff;
+pub mod folded;
+pub mod graph;
+pub mod options;
 pub mod parsed;
 pub mod raw;
+pub mod tree;
+pub mod view;
+pub mod warning;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComputeUnits {
     pub consumed: u64,
     pub budget: u64,
@@ -39,14 +56,41 @@ impl From<ParsedCuLog> for ComputeUnits {
     }
 }
 
+/// A single entry of a frame's execution, in the order it was logged.
+///
+/// Each variant points into the frame's matching convenience field, so the
+/// interleaving of logs and CPIs is preserved without storing anything twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameEvent {
+    /// An entry of `program_logs`
+    Log(usize),
+    /// An entry of `data_logs`
+    Data(usize),
+    /// An entry of `cpi_logs`
+    Cpi(usize),
+    /// The frame's `return_data` was set
+    Return,
+    /// The frame's `compute_log` was set
+    Compute,
+    /// An entry of `compute_checkpoints`
+    Checkpoint(usize),
+    /// An entry of `raw_logs` with no dedicated representation
+    Other(usize),
+}
+
 /// A generic structured representation of a program execution log.
 ///
 /// `StructuredLog` provides a hierarchical view of program execution, including:
 /// - The program's identifier and execution depth (`program_id`, `depth`)
 /// - The outcome of execution (`result`)
 /// - Logs emitted directly by the program (`program_logs`, `data_logs`, `return_data`, `compute_log`)
+/// - The units consumed with and without nested CPIs (`inclusive_consumed`, `self_consumed`)
+/// - Remaining compute units logged by `sol_log_compute_units` (`compute_checkpoints`)
 /// - Nested logs from CPI (cross-program invocation) calls (`cpi_logs`)
+/// - The order in which all of the above were logged (`events`)
 /// - Raw, unstructured logs that were parsed to build this representation (`raw_logs`)
+/// - Whether the logs were truncated while this frame was still open (`truncated`)
 ///
 /// This struct is parameterized over the types of each log component, allowing it to be reused
 /// in different contexts, such as raw log parsing, typed log rendering, or test scaffolding.
@@ -67,8 +111,13 @@ struct StructuredLog<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog>
     pub data_logs: Vec<DataLog>,
     pub return_data: Option<ReturnData>,
     pub compute_log: Option<ComputeUnits>,
+    pub inclusive_consumed: Option<u64>,
+    pub self_consumed: Option<u64>,
+    pub compute_checkpoints: Vec<u64>,
     pub cpi_logs: Vec<Self>,
+    pub events: Vec<FrameEvent>,
     pub raw_logs: Vec<RawLog>,
+    pub truncated: bool,
 }
 
 impl<Id, Program, Data, ReturnData, Err, RawLog>
@@ -77,120 +126,352 @@ where
     Id: Eq + Debug + Display,
     Program: Log<RawLog = RawLog>,
     Data: Log<RawLog = RawLog>,
+    RawLog: AsRef<str>,
 {
     #[allow(clippy::type_complexity)]
-    pub fn from_logs<Invoke, Success, Failed, Return, Compute, Other>(
-        logs: Vec<Log2<Invoke, Success, Failed, Program, Data, Return, Compute, Other>>,
-    ) -> Result<Vec<Self>>
+    pub fn from_logs<Invoke, Success, Failed, Return, Compute, Consumption, Truncated, Other>(
+        logs: Vec<
+            Log2<
+                Invoke,
+                Success,
+                Failed,
+                Program,
+                Data,
+                Return,
+                Compute,
+                Consumption,
+                Truncated,
+                Other,
+            >,
+        >,
+        options: StructuredParseOptions,
+    ) -> Result<TransactionLog<Self, RawLog>>
     where
         Invoke: Log<RawLog = RawLog> + InvokeLog<ProgramId = Id>,
         Success: Log<RawLog = RawLog> + SuccessLog<ProgramId = Id>,
         Failed: Log<RawLog = RawLog> + FailedLog<ProgramId = Id, Err = Err>,
         Return: Log<RawLog = RawLog> + ReturnLog<ProgramId = Id, Data = ReturnData>,
         Compute: Log<RawLog = RawLog> + ComputeUnitsLog<ProgramId = Id> + Into<ComputeUnits>,
+        Consumption: Log<RawLog = RawLog> + ConsumptionLog,
+        Truncated: Log<RawLog = RawLog>,
         Other: Log<RawLog = RawLog>,
     {
-        let mut stack = Vec::new();
-        let mut completed = Vec::new();
+        let mut stack = FrameStack::new(options);
 
-        for log in logs {
+        for (index, log) in logs.into_iter().enumerate() {
             match log {
                 Log2::Invoke(log) => {
-                    stack.push(FrameBuilder::new(
+                    let expected = stack
+                        .frames
+                        .last()
+                        .map_or(1, |parent| usize::from(parent.depth) + 1);
+                    let found = usize::from(log.depth());
+                    if found != expected {
+                        let log = log.raw_log().as_ref().to_string();
+                        stack.depth_mismatch(index, log, expected, found)?;
+                    }
+
+                    stack.frames.push(FrameBuilder::new(
                         log.program_id(),
                         log.depth(),
+                        index,
                         log.raw_log(),
                     ));
                 }
                 Log2::Success(log) => {
-                    let builder = stack
-                        .pop()
-                        .expect("Unmatched success log without a prior invoke");
-                    assert_eq!(
-                        builder.program_id,
+                    stack.close(
+                        index,
                         log.program_id(),
-                        "Mismatched success: expected {}, got {}",
-                        builder.program_id,
-                        log.program_id()
-                    );
-                    let structured = builder.finalize(ProgramResult::Success, log.raw_log());
-
-                    if let Some(parent) = stack.last_mut() {
-                        parent.cpi_logs.push(structured);
-                    } else {
-                        completed.push(structured);
-                    }
+                        ProgramResult::Success,
+                        log.raw_log(),
+                    )?;
                 }
                 Log2::Failed(log) => {
-                    let builder = stack
-                        .pop()
-                        .expect("Unmatched failed log without a prior invoke");
-                    assert_eq!(
-                        builder.program_id,
+                    stack.close(
+                        index,
                         log.program_id(),
-                        "Mismatched failed: expected {}, got {}",
-                        builder.program_id,
-                        log.program_id()
-                    );
-                    let structured = builder.finalize(ProgramResult::Err(log.err()), log.raw_log());
-
-                    if let Some(parent) = stack.last_mut() {
-                        parent.cpi_logs.push(structured);
-                    } else {
-                        completed.push(structured);
-                    }
-                }
-                Log2::Log(log) => {
-                    if let Some(top) = stack.last_mut() {
-                        top.push_program_log(log);
-                    }
+                        ProgramResult::Err(log.err()),
+                        log.raw_log(),
+                    )?;
                 }
-                Log2::Data(log) => {
-                    if let Some(top) = stack.last_mut() {
-                        top.push_data_log(log);
+                Log2::Log(log) => match stack.frames.last_mut() {
+                    Some(top) => top.push_program_log(log),
+                    None => stack.push_orphan(index, log.raw_log()),
+                },
+                Log2::Data(log) => match stack.frames.last_mut() {
+                    Some(top) => top.push_data_log(log),
+                    None => stack.push_orphan(index, log.raw_log()),
+                },
+                Log2::Return(log) => match stack.frames.last_mut() {
+                    Some(top) if top.program_id == log.program_id() => {
+                        top.set_return_data(log.data(), log.raw_log());
                     }
-                }
-                Log2::Return(log) => {
-                    if let Some(top) = stack.last_mut() {
-                        if top.program_id == log.program_id() {
-                            top.set_return_data(log.data(), log.raw_log());
-                        } else {
-                            top.push_raw(log.raw_log());
-                        }
+                    Some(top) => top.push_raw(log.raw_log()),
+                    None => stack.push_orphan(index, log.raw_log()),
+                },
+                Log2::Cu(log) => match stack.frames.last_mut() {
+                    Some(top) if top.program_id == log.program_id() => top.set_compute_log(log),
+                    Some(top) => top.push_raw(log.raw_log()),
+                    None => stack.push_orphan(index, log.raw_log()),
+                },
+                Log2::Consumption(log) => match stack.frames.last_mut() {
+                    Some(top) => top.push_checkpoint(log),
+                    None => stack.push_orphan(index, log.raw_log()),
+                },
+                Log2::Truncated(log) => {
+                    stack.truncated = true;
+                    for frame in stack.frames.iter_mut() {
+                        frame.truncated = true;
                     }
-                }
-                Log2::Cu(log) => {
-                    if let Some(top) = stack.last_mut() {
-                        if top.program_id == log.program_id() {
-                            top.set_compute_log(log);
-                        } else {
-                            top.push_raw(log.raw_log());
-                        }
+                    match stack.frames.last_mut() {
+                        Some(top) => top.push_raw(log.raw_log()),
+                        None => stack.push_orphan(index, log.raw_log()),
                     }
                 }
-                Log2::Other(log) => {
-                    if let Some(top) = stack.last_mut() {
-                        top.push_raw(log.raw_log());
-                    }
+                Log2::Other(log) => match stack.frames.last_mut() {
+                    Some(top) => top.push_raw(log.raw_log()),
+                    None => stack.push_orphan(index, log.raw_log()),
+                },
+            }
+        }
+
+        stack.finish()
+    }
+}
+
+/// Everything recovered from the logs of a single transaction
+struct TransactionLog<Frame, RawLog> {
+    frames: Vec<Frame>,
+    /// Logs that appeared while no frame was open
+    orphans: Vec<OrphanLog<RawLog>>,
+    truncated: bool,
+    warnings: Vec<StructuredParseWarning>,
+}
+
+/// A log that appeared outside of any frame
+struct OrphanLog<RawLog> {
+    index: usize,
+    position: usize,
+    log: RawLog,
+}
+
+/// The frames currently open while building structured logs, along with the
+/// completed top-level logs and any warnings recovered so far.
+struct FrameStack<Id, Err, ProgramLog, DataLog, ReturnData, RawLog> {
+    options: StructuredParseOptions,
+    truncated: bool,
+    orphans: Vec<OrphanLog<RawLog>>,
+    frames: Vec<FrameBuilder<Id, ProgramResult<Err>, ProgramLog, DataLog, ReturnData, RawLog>>,
+    completed: Vec<StructuredLog<Id, ProgramResult<Err>, ProgramLog, DataLog, ReturnData, RawLog>>,
+    warnings: Vec<StructuredParseWarning>,
+}
+
+impl<Id, Err, ProgramLog, DataLog, ReturnData, RawLog>
+    FrameStack<Id, Err, ProgramLog, DataLog, ReturnData, RawLog>
+where
+    Id: Eq + Debug + Display,
+    ProgramLog: Log<RawLog = RawLog>,
+    DataLog: Log<RawLog = RawLog>,
+    RawLog: AsRef<str>,
+{
+    fn new(options: StructuredParseOptions) -> Self {
+        Self {
+            options,
+            truncated: false,
+            orphans: vec![],
+            frames: vec![],
+            completed: vec![],
+            warnings: vec![],
+        }
+    }
+
+    /// Keeps a log that appeared while no frame was open.
+    fn push_orphan(&mut self, index: usize, log: RawLog) {
+        self.orphans.push(OrphanLog {
+            index,
+            position: self.completed.len(),
+            log,
+        });
+    }
+
+    /// Handles an invoke whose depth is not one more than the innermost frame's.
+    ///
+    /// Outside of strict mode the mismatch becomes a warning and, when the
+    /// invoke is too shallow, the open frames at or below its depth are closed
+    /// as incomplete.
+    fn depth_mismatch(
+        &mut self,
+        index: usize,
+        log: String,
+        expected: usize,
+        found: usize,
+    ) -> Result<()> {
+        if self.options.mode == StructuredParseMode::Strict {
+            return Err(LogParseError::DepthMismatch {
+                index,
+                log,
+                expected,
+                found,
+            });
+        }
+        self.warnings.push(StructuredParseWarning::DepthMismatch {
+            index,
+            log,
+            expected,
+            found,
+        });
+
+        while self
+            .frames
+            .last()
+            .is_some_and(|frame| usize::from(frame.depth) >= found)
+        {
+            self.close_incomplete();
+        }
+        Ok(())
+    }
+
+    /// Closes the innermost frame with a `success` or `failed` log.
+    fn close(
+        &mut self,
+        index: usize,
+        program_id: Id,
+        result: ProgramResult<Err>,
+        raw: RawLog,
+    ) -> Result<()> {
+        let best_effort = self.options.mode == StructuredParseMode::BestEffort;
+
+        let Some(top) = self.frames.last() else {
+            let log = raw.as_ref().to_string();
+            if !best_effort {
+                return Err(match result {
+                    ProgramResult::Err(_) => LogParseError::UnmatchedFailed { index, log },
+                    _ => LogParseError::UnmatchedSuccess { index, log },
+                });
+            }
+            self.warnings.push(match result {
+                ProgramResult::Err(_) => StructuredParseWarning::UnmatchedFailed { index, log },
+                _ => StructuredParseWarning::UnmatchedSuccess { index, log },
+            });
+            return Ok(());
+        };
+
+        if top.program_id != program_id {
+            let log = raw.as_ref().to_string();
+            let expected = top.program_id.to_string();
+            let found = program_id.to_string();
+            if !best_effort {
+                return Err(LogParseError::MismatchedProgramId {
+                    index,
+                    log,
+                    expected,
+                    found,
+                });
+            }
+            self.warnings
+                .push(StructuredParseWarning::MismatchedProgramId {
+                    index,
+                    log,
+                    expected,
+                    found,
+                });
+
+            // unwind to the closest frame this log belongs to, if there is one
+            let Some(position) = self
+                .frames
+                .iter()
+                .rposition(|frame| frame.program_id == program_id)
+            else {
+                if let Some(top) = self.frames.last_mut() {
+                    top.push_raw(raw);
                 }
+                return Ok(());
+            };
+            while self.frames.len() > position + 1 {
+                self.close_incomplete();
             }
         }
 
-        assert!(
-            stack.is_empty(),
-            "Unbalanced log stack: {} frames left",
-            stack.len()
-        );
-        Ok(completed)
+        if let Some(builder) = self.frames.pop() {
+            let structured = builder.finalize(result, raw);
+            self.attach(structured);
+        }
+        Ok(())
+    }
+
+    /// Closes the innermost frame without a closing log.
+    fn close_incomplete(&mut self) {
+        if let Some(builder) = self.frames.pop() {
+            self.warnings.push(StructuredParseWarning::IncompleteFrame {
+                index: builder.index,
+                log: builder.raw_logs[0].as_ref().to_string(),
+            });
+            let structured = builder.build(ProgramResult::Incomplete);
+            self.attach(structured);
+        }
+    }
+
+    fn attach(
+        &mut self,
+        structured: StructuredLog<Id, ProgramResult<Err>, ProgramLog, DataLog, ReturnData, RawLog>,
+    ) {
+        if let Some(parent) = self.frames.last_mut() {
+            parent.push_cpi_log(structured);
+        } else {
+            self.completed.push(structured);
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn finish(
+        mut self,
+    ) -> Result<
+        TransactionLog<
+            StructuredLog<Id, ProgramResult<Err>, ProgramLog, DataLog, ReturnData, RawLog>,
+            RawLog,
+        >,
+    > {
+        if let Some(innermost) = self.frames.last() {
+            // frames left open by a `Log truncated` marker are expected, so they
+            // are closed as incomplete regardless of the parse mode
+            if self.options.mode == StructuredParseMode::Strict && !self.truncated {
+                return Err(LogParseError::UnbalancedStack {
+                    index: innermost.index,
+                    log: innermost.raw_logs[0].as_ref().to_string(),
+                    open: self.frames.len(),
+                });
+            }
+            while !self.frames.is_empty() {
+                self.close_incomplete();
+            }
+        }
+        Ok(TransactionLog {
+            frames: self.completed,
+            orphans: self.orphans,
+            truncated: self.truncated,
+            warnings: self.warnings,
+        })
     }
 }
 
 enum ProgramResult<Err> {
     Success,
     Err(Err),
+    Incomplete,
 }
 
-pub(crate) enum Log2<Invoke, Success, Failed, Program, Data, Return, Cu, Other> {
+pub(crate) enum Log2<
+    Invoke,
+    Success,
+    Failed,
+    Program,
+    Data,
+    Return,
+    Cu,
+    Consumption,
+    Truncated,
+    Other,
+> {
     Invoke(Invoke),
     Success(Success),
     Failed(Failed),
@@ -198,6 +479,8 @@ pub(crate) enum Log2<Invoke, Success, Failed, Program, Data, Return, Cu, Other>
     Data(Data),
     Return(Return),
     Cu(Cu),
+    Consumption(Consumption),
+    Truncated(Truncated),
     Other(Other),
 }
 
@@ -210,6 +493,8 @@ impl<'a> From<RawLog<'a>>
         RawDataLog<'a>,
         RawReturnLog<'a>,
         RawCuLog<'a>,
+        RawConsumptionLog<'a>,
+        RawTruncatedLog<'a>,
         RawOtherLog<'a>,
     >
 {
@@ -222,6 +507,8 @@ impl<'a> From<RawLog<'a>>
             RawLog::Data(raw_data_log) => Log2::Data(raw_data_log),
             RawLog::Return(raw_return_log) => Log2::Return(raw_return_log),
             RawLog::Cu(raw_cu_log) => Log2::Cu(raw_cu_log),
+            RawLog::Consumption(raw_consumption_log) => Log2::Consumption(raw_consumption_log),
+            RawLog::Truncated(raw_truncated_log) => Log2::Truncated(raw_truncated_log),
             RawLog::Other(raw_other) => Log2::Other(raw_other),
         }
     }
@@ -236,6 +523,8 @@ impl From<ParsedLog>
         ParsedDataLog,
         ParsedReturnLog,
         ParsedCuLog,
+        ParsedConsumptionLog,
+        ParsedTruncatedLog,
         ParsedOtherLog,
     >
 {
@@ -248,6 +537,8 @@ impl From<ParsedLog>
             ParsedLog::Data(data_log) => Log2::Data(data_log),
             ParsedLog::Return(return_log) => Log2::Return(return_log),
             ParsedLog::Cu(cu_log) => Log2::Cu(cu_log),
+            ParsedLog::Consumption(consumption_log) => Log2::Consumption(consumption_log),
+            ParsedLog::Truncated(truncated_log) => Log2::Truncated(truncated_log),
             ParsedLog::Other(other) => Log2::Other(other),
         }
     }
@@ -294,15 +585,81 @@ pub(crate) trait ComputeUnitsLog {
     fn program_id(&self) -> Self::ProgramId;
 }
 
+pub(crate) trait ConsumptionLog {
+    fn remaining(&self) -> u64;
+}
+
+/// The index of the first top-level frame that failed
+fn failed_instruction<Frame: FrameView>(frames: &[Frame]) -> Option<usize> {
+    frames
+        .iter()
+        .position(|frame| matches!(frame.result(), FrameResult::Err(_)))
+}
+
+/// Whether every top-level frame ran to a `success` log and no logs were lost
+fn succeeded<Frame: FrameView>(frames: &[Frame], truncated: bool) -> bool {
+    !truncated
+        && frames
+            .iter()
+            .all(|frame| matches!(frame.result(), FrameResult::Success))
+}
+
+/// The units consumed by all top-level frames that logged their consumption
+fn compute_units_consumed<Frame: FrameView>(frames: &[Frame]) -> u64 {
+    frames
+        .iter()
+        .filter_map(|frame| frame.compute_log())
+        .map(|compute_log| compute_log.consumed)
+        .sum()
+}
+
+/// Writes `value` as a quoted and escaped JSON string
+pub(crate) fn write_json_str(f: &mut impl Write, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// The name in an `Instruction: <name>` program log, as logged by Anchor and
+/// most native programs when they start handling an instruction
+pub(crate) fn instruction_name(msg: &str) -> Option<&str> {
+    msg.trim()
+        .strip_prefix("Instruction: ")
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// The units consumed between consecutive compute checkpoints
+pub(crate) fn checkpoint_deltas(checkpoints: &[u64]) -> Vec<u64> {
+    checkpoints
+        .windows(2)
+        .map(|pair| pair[0].saturating_sub(pair[1]))
+        .collect()
+}
+
 struct FrameBuilder<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog> {
     program_id: Id,
     depth: u8,
+    index: usize,
+    truncated: bool,
     program_logs: Vec<ProgramLog>,
     data_logs: Vec<DataLog>,
     return_data: Option<ReturnData>,
     compute_log: Option<ComputeUnits>,
+    compute_checkpoints: Vec<u64>,
     raw_logs: Vec<RawLog>,
     cpi_logs: Vec<StructuredLog<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog>>,
+    events: Vec<FrameEvent>,
 }
 
 impl<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog>
@@ -312,34 +669,50 @@ where
     ProgramLog: Log<RawLog = RawLog>,
     DataLog: Log<RawLog = RawLog>,
 {
-    fn new(program_id: Id, depth: u8, raw: RawLog) -> Self {
+    fn new(program_id: Id, depth: u8, index: usize, raw: RawLog) -> Self {
         Self {
             program_id,
             depth,
+            index,
+            truncated: false,
             program_logs: vec![],
             data_logs: vec![],
             return_data: None,
             compute_log: None,
+            compute_checkpoints: vec![],
             raw_logs: vec![raw],
             cpi_logs: vec![],
+            events: vec![],
         }
     }
 
     fn push_program_log(&mut self, log: ProgramLog) {
+        self.events.push(FrameEvent::Log(self.program_logs.len()));
         self.raw_logs.push(log.raw_log());
         self.program_logs.push(log);
     }
 
     fn push_data_log(&mut self, log: DataLog) {
+        self.events.push(FrameEvent::Data(self.data_logs.len()));
         self.raw_logs.push(log.raw_log());
         self.data_logs.push(log);
     }
 
+    fn push_cpi_log(
+        &mut self,
+        log: StructuredLog<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog>,
+    ) {
+        self.events.push(FrameEvent::Cpi(self.cpi_logs.len()));
+        self.cpi_logs.push(log);
+    }
+
     fn push_raw(&mut self, raw: RawLog) {
+        self.events.push(FrameEvent::Other(self.raw_logs.len()));
         self.raw_logs.push(raw);
     }
 
     fn set_return_data(&mut self, data: ReturnData, raw: RawLog) {
+        self.events.push(FrameEvent::Return);
         self.raw_logs.push(raw);
         self.return_data = Some(data);
     }
@@ -348,20 +721,50 @@ where
     where
         ComputeLog: Log<RawLog = RawLog> + Into<ComputeUnits>,
     {
+        self.events.push(FrameEvent::Compute);
         self.raw_logs.push(log.raw_log());
         self.compute_log = Some(log.into());
     }
 
+    fn push_checkpoint<Consumption>(&mut self, log: Consumption)
+    where
+        Consumption: Log<RawLog = RawLog> + ConsumptionLog,
+    {
+        self.events
+            .push(FrameEvent::Checkpoint(self.compute_checkpoints.len()));
+        self.raw_logs.push(log.raw_log());
+        self.compute_checkpoints.push(log.remaining());
+    }
+
     fn finalize(
         mut self,
         result: ProgramResult,
         final_raw: RawLog,
     ) -> StructuredLog<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog> {
         self.raw_logs.push(final_raw);
+        self.build(result)
+    }
+
+    fn build(
+        mut self,
+        result: ProgramResult,
+    ) -> StructuredLog<Id, ProgramResult, ProgramLog, DataLog, ReturnData, RawLog> {
         self.raw_logs.shrink_to_fit();
         self.cpi_logs.shrink_to_fit();
         self.data_logs.shrink_to_fit();
         self.program_logs.shrink_to_fit();
+        self.events.shrink_to_fit();
+        self.compute_checkpoints.shrink_to_fit();
+
+        // builtin programs log no consumption, so their units stay
+        // attributed to the frame that invoked them
+        let inclusive_consumed = self.compute_log.as_ref().map(|log| log.consumed);
+        let self_consumed = inclusive_consumed.map(|inclusive| {
+            self.cpi_logs
+                .iter()
+                .filter_map(|cpi_log| cpi_log.inclusive_consumed)
+                .fold(inclusive, u64::saturating_sub)
+        });
 
         StructuredLog {
             program_id: self.program_id,
@@ -371,8 +774,13 @@ where
             data_logs: self.data_logs,
             return_data: self.return_data,
             compute_log: self.compute_log,
+            inclusive_consumed,
+            self_consumed,
+            compute_checkpoints: self.compute_checkpoints,
             cpi_logs: self.cpi_logs,
+            events: self.events,
             raw_logs: self.raw_logs,
+            truncated: self.truncated,
         }
     }
 }
@@ -0,0 +1,41 @@
+/// How strictly structural inconsistencies in the logs are treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StructuredParseMode {
+    /// Every structural inconsistency is returned as an error
+    #[default]
+    Strict,
+    /// Frames still open when the logs end are closed as incomplete, and
+    /// invokes at an unexpected depth are accepted with a warning
+    Lenient,
+    /// Like [`StructuredParseMode::Lenient`], but also recovers from unmatched or
+    /// mismatched `success`/`failed` logs instead of failing
+    BestEffort,
+}
+
+/// Options controlling how structured logs are built
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructuredParseOptions {
+    pub mode: StructuredParseMode,
+}
+
+impl StructuredParseOptions {
+    pub fn strict() -> Self {
+        Self {
+            mode: StructuredParseMode::Strict,
+        }
+    }
+
+    pub fn lenient() -> Self {
+        Self {
+            mode: StructuredParseMode::Lenient,
+        }
+    }
+
+    pub fn best_effort() -> Self {
+        Self {
+            mode: StructuredParseMode::BestEffort,
+        }
+    }
+}
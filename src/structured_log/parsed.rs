@@ -1,37 +1,151 @@
 use solana_pubkey::Pubkey;
 
 use crate::{
+    anchor_error::AnchorError,
+    instruction_error::ParsedInstructionError,
     parsed_log::{ParsedDataLog, ParsedLog, ParsedProgramLog},
+    program_panic::ProgramPanic,
     Result,
 };
 
-use super::{ComputeUnits, Log2};
+use super::{
+    checkpoint_deltas, compute_units_consumed, failed_instruction, options::StructuredParseOptions,
+    succeeded, warning::StructuredParseWarning, ComputeUnits, FrameEvent, Log2,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsedStructuredLog {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::pubkey"))]
     pub program_id: Pubkey,
     pub depth: u8,
+    /// The name from a leading `Instruction: <name>` program log
+    pub instruction_name: Option<String>,
     pub result: ParsedProgramResult,
     pub program_logs: Vec<ParsedProgramLog>,
     pub data_logs: Vec<ParsedDataLog>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_helpers::option_bytes"))]
     pub return_data: Option<Vec<u8>>,
     pub compute_log: Option<ComputeUnits>,
+    /// The units consumed by this frame including its CPIs, if it logged them
+    pub inclusive_consumed: Option<u64>,
+    /// The units consumed by this frame excluding the CPIs that logged theirs
+    pub self_consumed: Option<u64>,
+    /// Remaining compute units at each `Program consumption` log
+    pub compute_checkpoints: Vec<u64>,
     pub cpi_logs: Vec<Self>,
+    pub events: Vec<FrameEvent>,
     pub raw_logs: Vec<String>,
+    pub truncated: bool,
+    /// The Anchor error logged by this frame, if it failed with one
+    pub anchor_error: Option<AnchorError>,
+    /// The panic logged by this frame, if it failed with one
+    pub panic: Option<ProgramPanic>,
 }
 
 impl ParsedStructuredLog {
     pub fn from_parsed_logs(logs: Vec<ParsedLog>) -> Result<Vec<Self>> {
-        let log2: Vec<_> = logs.into_iter().map(Log2::from).collect();
-        let structured_log = helper_code::ParsedStructuredLogHelper::from_logs(log2)?;
-        Ok(structured_log.into_iter().map(Self::from).collect())
+        Self::from_parsed_logs_with_options(logs, StructuredParseOptions::default())
+            .map(|(structured_logs, _)| structured_logs)
+    }
+
+    pub fn from_parsed_logs_with_options(
+        logs: Vec<ParsedLog>,
+        options: StructuredParseOptions,
+    ) -> Result<(Vec<Self>, Vec<StructuredParseWarning>)> {
+        let transaction_log = ParsedTransactionLog::from_parsed_logs_with_options(logs, options)?;
+        Ok((transaction_log.frames, transaction_log.warnings))
+    }
+
+    /// The units consumed between each pair of consecutive `compute_checkpoints`
+    pub fn checkpoint_deltas(&self) -> Vec<u64> {
+        checkpoint_deltas(&self.compute_checkpoints)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ParsedProgramResult {
     Success,
-    Err(String),
+    Err(ParsedInstructionError),
+    /// The frame was never closed, e.g. because the logs were truncated
+    Incomplete,
+}
+
+/// The structured logs of a whole transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedTransactionLog {
+    /// The top-level frames, one per instruction, in order
+    pub frames: Vec<ParsedStructuredLog>,
+    /// Logs that appeared outside of any frame
+    pub orphans: Vec<ParsedOrphanLog>,
+    /// Whether every top-level frame succeeded, false if any failed, was left
+    /// incomplete, or the logs were truncated
+    pub success: bool,
+    /// The index in `frames` of the instruction that failed
+    pub failed_instruction: Option<usize>,
+    /// The units consumed by the top-level frames that logged their consumption
+    pub compute_units_consumed: u64,
+    /// Whether a `Log truncated` marker was seen
+    pub truncated: bool,
+    pub warnings: Vec<StructuredParseWarning>,
+    pub signature: Option<String>,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+}
+
+impl ParsedTransactionLog {
+    pub fn from_parsed_logs(logs: Vec<ParsedLog>) -> Result<Self> {
+        Self::from_parsed_logs_with_options(logs, StructuredParseOptions::default())
+    }
+
+    pub fn from_parsed_logs_with_options(
+        logs: Vec<ParsedLog>,
+        options: StructuredParseOptions,
+    ) -> Result<Self> {
+        let log2: Vec<_> = logs.into_iter().map(Log2::from).collect();
+        let transaction_log = helper_code::ParsedStructuredLogHelper::from_logs(log2, options)?;
+        let frames: Vec<_> = transaction_log
+            .frames
+            .into_iter()
+            .map(ParsedStructuredLog::from)
+            .collect();
+        let failed_instruction = failed_instruction(&frames);
+
+        Ok(Self {
+            success: succeeded(&frames, transaction_log.truncated),
+            failed_instruction,
+            compute_units_consumed: compute_units_consumed(&frames),
+            frames,
+            orphans: transaction_log
+                .orphans
+                .into_iter()
+                .map(|orphan| ParsedOrphanLog {
+                    index: orphan.index,
+                    position: orphan.position,
+                    log: orphan.log,
+                })
+                .collect(),
+            truncated: transaction_log.truncated,
+            warnings: transaction_log.warnings,
+            signature: None,
+            slot: None,
+            block_time: None,
+        })
+    }
+}
+
+/// A log that appeared outside of any frame, such as a runtime message
+/// between two instructions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedOrphanLog {
+    /// The index of the log in the transaction's logs
+    pub index: usize,
+    /// The number of top-level frames that completed before the log
+    pub position: usize,
+    pub log: String,
 }
 
 /* *************************************************************************** *
@@ -42,34 +156,82 @@ mod helper_code {
     use solana_pubkey::Pubkey;
 
     use crate::{
+        anchor_error::AnchorError,
+        instruction_error::ParsedInstructionError,
         parsed_log::{ParsedDataLog, ParsedProgramLog},
-        structured_log::{ProgramResult, StructuredLog},
+        program_panic::ProgramPanic,
+        structured_log::{instruction_name, FrameEvent, ProgramResult, StructuredLog},
     };
 
     use super::{ParsedProgramResult, ParsedStructuredLog};
 
     impl From<ParsedStructuredLogHelper> for ParsedStructuredLog {
         fn from(value: ParsedStructuredLogHelper) -> Self {
+            let (anchor_error, panic) = match value.result {
+                ProgramResult::Err(_) => (
+                    AnchorError::from_msgs(value.program_logs.iter().map(|log| log.msg.as_str())),
+                    find_panic(&value),
+                ),
+                _ => (None, None),
+            };
+
             Self {
                 program_id: value.program_id,
                 depth: value.depth,
+                instruction_name: value
+                    .program_logs
+                    .first()
+                    .and_then(|log| instruction_name(&log.msg))
+                    .map(String::from),
                 result: match value.result {
                     ProgramResult::Success => ParsedProgramResult::Success,
+                    ProgramResult::Incomplete => ParsedProgramResult::Incomplete,
                     ProgramResult::Err(err) => ParsedProgramResult::Err(err),
                 },
                 program_logs: value.program_logs,
                 data_logs: value.data_logs,
                 return_data: value.return_data,
                 compute_log: value.compute_log,
+                inclusive_consumed: value.inclusive_consumed,
+                self_consumed: value.self_consumed,
+                compute_checkpoints: value.compute_checkpoints,
                 cpi_logs: value.cpi_logs.into_iter().map(Self::from).collect(),
+                events: value.events,
                 raw_logs: value.raw_logs,
+                truncated: value.truncated,
+                anchor_error,
+                panic,
             }
         }
     }
 
+    /// Finds the last panic logged by the frame. A message split onto its own
+    /// line is picked up from the unrecognized log right after the panic.
+    fn find_panic(value: &ParsedStructuredLogHelper) -> Option<ProgramPanic> {
+        let mut panic = None;
+        let mut events = value.events.iter().peekable();
+
+        while let Some(event) = events.next() {
+            let FrameEvent::Log(index) = event else {
+                continue;
+            };
+            let Some(mut parsed) = value.program_logs[*index].panic() else {
+                continue;
+            };
+            if parsed.message.is_empty() {
+                if let Some(FrameEvent::Other(raw_index)) = events.peek() {
+                    parsed.message = value.raw_logs[*raw_index].trim().to_string();
+                }
+            }
+            panic = Some(parsed);
+        }
+
+        panic
+    }
+
     pub type ParsedStructuredLogHelper = StructuredLog<
         Pubkey,
-        ProgramResult<String>,
+        ProgramResult<ParsedInstructionError>,
         ParsedProgramLog,
         ParsedDataLog,
         Vec<u8>,
@@ -3,35 +3,155 @@ use crate::{
     Result,
 };
 
-use super::{ComputeUnits, Log2};
+use super::{
+    checkpoint_deltas, compute_units_consumed, failed_instruction, options::StructuredParseOptions,
+    succeeded, warning::StructuredParseWarning, ComputeUnits, FrameEvent, Log2,
+};
 
 /// A Raw Structured Log
+///
+/// Like [`RawLog`], this deserializes zero-copy and rejects escaped strings.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawStructuredLog<'a> {
     pub program_id: &'a str,
     pub depth: u8,
+    /// The name from a leading `Instruction: <name>` program log
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub instruction_name: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub result: RawProgramResult<'a>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub program_logs: Vec<RawProgramLog<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub data_logs: Vec<RawDataLog<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub return_data: Option<&'a str>,
     pub compute_log: Option<ComputeUnits>,
+    /// The units consumed by this frame including its CPIs, if it logged them
+    pub inclusive_consumed: Option<u64>,
+    /// The units consumed by this frame excluding the CPIs that logged theirs
+    pub self_consumed: Option<u64>,
+    /// Remaining compute units at each `Program consumption` log
+    pub compute_checkpoints: Vec<u64>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub cpi_logs: Vec<RawStructuredLog<'a>>,
+    pub events: Vec<FrameEvent>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub raw_logs: Vec<&'a str>,
+    pub truncated: bool,
 }
 
 impl<'a> RawStructuredLog<'a> {
     pub fn from_raw_logs(logs: Vec<RawLog<'a>>) -> Result<Vec<Self>> {
-        let log2: Vec<_> = logs.into_iter().map(Log2::from).collect();
-        let structured_log = helper_code::RawStructuredLogHelper::from_logs(log2)?;
-        Ok(structured_log.into_iter().map(Self::from).collect())
+        Self::from_raw_logs_with_options(logs, StructuredParseOptions::default())
+            .map(|(structured_logs, _)| structured_logs)
+    }
+
+    pub fn from_raw_logs_with_options(
+        logs: Vec<RawLog<'a>>,
+        options: StructuredParseOptions,
+    ) -> Result<(Vec<Self>, Vec<StructuredParseWarning>)> {
+        let transaction_log = RawTransactionLog::from_raw_logs_with_options(logs, options)?;
+        Ok((transaction_log.frames, transaction_log.warnings))
+    }
+
+    /// The units consumed between each pair of consecutive `compute_checkpoints`
+    pub fn checkpoint_deltas(&self) -> Vec<u64> {
+        checkpoint_deltas(&self.compute_checkpoints)
     }
 }
 
 /// A Raw Program Result
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RawProgramResult<'a> {
     Success,
     Err(&'a str),
+    /// The frame was never closed, e.g. because the logs were truncated
+    Incomplete,
+}
+
+/// The structured logs of a whole transaction
+///
+/// Like [`RawLog`], this deserializes zero-copy and rejects escaped strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawTransactionLog<'a> {
+    /// The top-level frames, one per instruction, in order
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub frames: Vec<RawStructuredLog<'a>>,
+    /// Logs that appeared outside of any frame
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub orphans: Vec<RawOrphanLog<'a>>,
+    /// Whether every top-level frame succeeded, false if any failed, was left
+    /// incomplete, or the logs were truncated
+    pub success: bool,
+    /// The index in `frames` of the instruction that failed
+    pub failed_instruction: Option<usize>,
+    /// The units consumed by the top-level frames that logged their consumption
+    pub compute_units_consumed: u64,
+    /// Whether a `Log truncated` marker was seen
+    pub truncated: bool,
+    pub warnings: Vec<StructuredParseWarning>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub signature: Option<&'a str>,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+}
+
+impl<'a> RawTransactionLog<'a> {
+    pub fn from_raw_logs(logs: Vec<RawLog<'a>>) -> Result<Self> {
+        Self::from_raw_logs_with_options(logs, StructuredParseOptions::default())
+    }
+
+    pub fn from_raw_logs_with_options(
+        logs: Vec<RawLog<'a>>,
+        options: StructuredParseOptions,
+    ) -> Result<Self> {
+        let log2: Vec<_> = logs.into_iter().map(Log2::from).collect();
+        let transaction_log = helper_code::RawStructuredLogHelper::from_logs(log2, options)?;
+        let frames: Vec<_> = transaction_log
+            .frames
+            .into_iter()
+            .map(RawStructuredLog::from)
+            .collect();
+        let failed_instruction = failed_instruction(&frames);
+
+        Ok(Self {
+            success: succeeded(&frames, transaction_log.truncated),
+            failed_instruction,
+            compute_units_consumed: compute_units_consumed(&frames),
+            frames,
+            orphans: transaction_log
+                .orphans
+                .into_iter()
+                .map(|orphan| RawOrphanLog {
+                    index: orphan.index,
+                    position: orphan.position,
+                    log: orphan.log,
+                })
+                .collect(),
+            truncated: transaction_log.truncated,
+            warnings: transaction_log.warnings,
+            signature: None,
+            slot: None,
+            block_time: None,
+        })
+    }
+}
+
+/// A log that appeared outside of any frame, such as a runtime message
+/// between two instructions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawOrphanLog<'a> {
+    /// The index of the log in the transaction's logs
+    pub index: usize,
+    /// The number of top-level frames that completed before the log
+    pub position: usize,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub log: &'a str,
 }
 
 /* *************************************************************************** *
@@ -41,7 +161,7 @@ pub enum RawProgramResult<'a> {
 mod helper_code {
     use crate::{
         raw_log::{RawDataLog, RawProgramLog},
-        structured_log::{ProgramResult, StructuredLog},
+        structured_log::{instruction_name, ProgramResult, StructuredLog},
     };
 
     use super::{RawProgramResult, RawStructuredLog};
@@ -51,16 +171,26 @@ mod helper_code {
             Self {
                 program_id: value.program_id,
                 depth: value.depth,
+                instruction_name: value
+                    .program_logs
+                    .first()
+                    .and_then(|log| instruction_name(log.msg)),
                 result: match value.result {
                     ProgramResult::Success => RawProgramResult::Success,
+                    ProgramResult::Incomplete => RawProgramResult::Incomplete,
                     ProgramResult::Err(err) => RawProgramResult::Err(err),
                 },
                 program_logs: value.program_logs,
                 data_logs: value.data_logs,
                 return_data: value.return_data,
                 compute_log: value.compute_log,
+                inclusive_consumed: value.inclusive_consumed,
+                self_consumed: value.self_consumed,
+                compute_checkpoints: value.compute_checkpoints,
                 cpi_logs: value.cpi_logs.into_iter().map(Self::from).collect(),
+                events: value.events,
                 raw_logs: value.raw_logs,
+                truncated: value.truncated,
             }
         }
     }
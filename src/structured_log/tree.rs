@@ -0,0 +1,215 @@
+use std::fmt::{self, Display};
+
+use super::{
+    parsed::ParsedStructuredLog,
+    raw::RawStructuredLog,
+    view::{FrameResult, FrameView},
+    FrameEvent,
+};
+
+/// Renders structured logs as an indented call tree.
+///
+/// ```text
+/// D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit) success [8388 of 1400000 CU]
+/// ├── log: Instruction: Deposit
+/// ├── 11111111111111111111111111111111 success
+/// │   └── log: Transfer complete
+/// └── data: 8 + 32 bytes
+/// ```
+///
+/// The `Display` implementations of [`RawStructuredLog`] and
+/// [`ParsedStructuredLog`] use the default formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeFormatter {
+    pub program_logs: bool,
+    pub data_logs: bool,
+    pub compute_units: bool,
+    pub other_logs: bool,
+    pub ascii: bool,
+}
+
+impl Default for TreeFormatter {
+    fn default() -> Self {
+        Self {
+            program_logs: true,
+            data_logs: true,
+            compute_units: true,
+            other_logs: true,
+            ascii: false,
+        }
+    }
+}
+
+impl TreeFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `Program log` messages
+    pub fn program_logs(mut self, show: bool) -> Self {
+        self.program_logs = show;
+        self
+    }
+
+    /// Show the decoded lengths of `Program data` and `Program return` logs
+    pub fn data_logs(mut self, show: bool) -> Self {
+        self.data_logs = show;
+        self
+    }
+
+    /// Show consumed compute units and `Program consumption` checkpoints
+    pub fn compute_units(mut self, show: bool) -> Self {
+        self.compute_units = show;
+        self
+    }
+
+    /// Show logs with no dedicated representation
+    pub fn other_logs(mut self, show: bool) -> Self {
+        self.other_logs = show;
+        self
+    }
+
+    /// Draw the tree with ASCII instead of box-drawing characters
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Wraps a frame so it renders with this formatter
+    pub fn display<'a, F: FrameView>(&'a self, frame: &'a F) -> impl Display + 'a {
+        DisplayTree {
+            formatter: self,
+            frame,
+        }
+    }
+
+    pub fn write<F: FrameView>(&self, f: &mut impl fmt::Write, frame: &F) -> fmt::Result {
+        self.write_frame(f, frame, "")
+    }
+
+    fn write_frame<F: FrameView>(
+        &self,
+        f: &mut impl fmt::Write,
+        frame: &F,
+        prefix: &str,
+    ) -> fmt::Result {
+        self.write_header(f, frame)?;
+
+        let entries: Vec<_> = frame
+            .events()
+            .iter()
+            .filter(|event| self.shows(event))
+            .collect();
+
+        let (branch, last_branch, continuation, last_continuation) = if self.ascii {
+            ("|-- ", "`-- ", "|   ", "    ")
+        } else {
+            ("├── ", "└── ", "│   ", "    ")
+        };
+
+        for (position, event) in entries.iter().enumerate() {
+            let last = position + 1 == entries.len();
+            let branch = if last { last_branch } else { branch };
+            write!(f, "\n{prefix}{branch}")?;
+
+            match event {
+                FrameEvent::Cpi(index) => {
+                    let continuation = if last {
+                        last_continuation
+                    } else {
+                        continuation
+                    };
+                    let prefix = format!("{prefix}{continuation}");
+                    self.write_frame(f, &frame.cpi_logs()[*index], &prefix)?;
+                }
+                FrameEvent::Log(index) => {
+                    write!(f, "log: {}", single_line(frame.program_log(*index)))?;
+                }
+                FrameEvent::Data(index) => {
+                    let lens: Vec<_> = frame
+                        .data_log_lens(*index)
+                        .iter()
+                        .map(usize::to_string)
+                        .collect();
+                    write!(f, "data: {} bytes", lens.join(" + "))?;
+                }
+                FrameEvent::Return => {
+                    write!(f, "return: {} bytes", frame.return_data_len().unwrap_or(0))?;
+                }
+                FrameEvent::Checkpoint(index) => {
+                    let remaining = frame.compute_checkpoints()[*index];
+                    write!(f, "consumption: {remaining} units remaining")?;
+                }
+                FrameEvent::Compute => {}
+                FrameEvent::Other(index) => {
+                    write!(f, "{}", single_line(frame.raw_log(*index).trim()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_header<F: FrameView>(&self, f: &mut impl fmt::Write, frame: &F) -> fmt::Result {
+        write!(f, "{}", frame.program_id())?;
+        if let Some(name) = frame.instruction_name() {
+            write!(f, " ({name})")?;
+        }
+
+        match frame.result() {
+            FrameResult::Success => write!(f, " success")?,
+            FrameResult::Err(err) => write!(f, " failed: {err}")?,
+            FrameResult::Incomplete => write!(f, " incomplete")?,
+        }
+
+        if self.compute_units {
+            if let Some(compute) = frame.compute_log() {
+                write!(f, " [{} of {} CU]", compute.consumed, compute.budget)?;
+            }
+        }
+        if frame.truncated() {
+            write!(f, " (truncated)")?;
+        }
+
+        Ok(())
+    }
+
+    fn shows(&self, event: &FrameEvent) -> bool {
+        match event {
+            FrameEvent::Cpi(_) => true,
+            FrameEvent::Log(_) => self.program_logs,
+            FrameEvent::Data(_) | FrameEvent::Return => self.data_logs,
+            FrameEvent::Checkpoint(_) => self.compute_units,
+            FrameEvent::Compute => false,
+            FrameEvent::Other(_) => self.other_logs,
+        }
+    }
+}
+
+struct DisplayTree<'a, F> {
+    formatter: &'a TreeFormatter,
+    frame: &'a F,
+}
+
+impl<F: FrameView> Display for DisplayTree<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter.write(f, self.frame)
+    }
+}
+
+/// Keeps multi-line messages, such as panics, on a single tree line
+fn single_line(msg: &str) -> String {
+    msg.replace('\n', "\\n")
+}
+
+impl Display for RawStructuredLog<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        TreeFormatter::default().write(f, self)
+    }
+}
+
+impl Display for ParsedStructuredLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        TreeFormatter::default().write(f, self)
+    }
+}
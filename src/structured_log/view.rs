@@ -0,0 +1,186 @@
+use std::fmt::Display;
+
+use super::{
+    parsed::{ParsedProgramResult, ParsedStructuredLog},
+    raw::{RawProgramResult, RawStructuredLog},
+    ComputeUnits, FrameEvent,
+};
+
+/// The outcome of a frame, borrowed from either kind of structured log
+#[derive(Clone, Copy)]
+pub enum FrameResult<'a> {
+    Success,
+    Err(&'a dyn Display),
+    Incomplete,
+}
+
+/// A read-only view of a structured log frame.
+///
+/// Implemented by both [`RawStructuredLog`] and [`ParsedStructuredLog`] so
+/// renderers and exporters can walk either kind of call tree.
+pub trait FrameView: Sized {
+    fn program_id(&self) -> String;
+    fn depth(&self) -> u8;
+    fn instruction_name(&self) -> Option<&str>;
+    fn result(&self) -> FrameResult<'_>;
+    fn compute_log(&self) -> Option<&ComputeUnits>;
+    fn inclusive_consumed(&self) -> Option<u64>;
+    fn self_consumed(&self) -> Option<u64>;
+    fn compute_checkpoints(&self) -> &[u64];
+    fn cpi_logs(&self) -> &[Self];
+    fn events(&self) -> &[FrameEvent];
+    fn truncated(&self) -> bool;
+
+    /// The raw log at `index`
+    fn raw_log(&self, index: usize) -> &str;
+
+    /// The message of the program log at `index`
+    fn program_log(&self, index: usize) -> &str;
+
+    /// The decoded length of each field of the data log at `index`
+    fn data_log_lens(&self, index: usize) -> Vec<usize>;
+
+    /// The decoded length of the return data, if any was set
+    fn return_data_len(&self) -> Option<usize>;
+}
+
+impl FrameView for RawStructuredLog<'_> {
+    fn program_id(&self) -> String {
+        self.program_id.to_string()
+    }
+
+    fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    fn instruction_name(&self) -> Option<&str> {
+        self.instruction_name
+    }
+
+    fn result(&self) -> FrameResult<'_> {
+        match &self.result {
+            RawProgramResult::Success => FrameResult::Success,
+            RawProgramResult::Err(err) => FrameResult::Err(err),
+            RawProgramResult::Incomplete => FrameResult::Incomplete,
+        }
+    }
+
+    fn compute_log(&self) -> Option<&ComputeUnits> {
+        self.compute_log.as_ref()
+    }
+
+    fn inclusive_consumed(&self) -> Option<u64> {
+        self.inclusive_consumed
+    }
+
+    fn self_consumed(&self) -> Option<u64> {
+        self.self_consumed
+    }
+
+    fn compute_checkpoints(&self) -> &[u64] {
+        &self.compute_checkpoints
+    }
+
+    fn cpi_logs(&self) -> &[Self] {
+        &self.cpi_logs
+    }
+
+    fn events(&self) -> &[FrameEvent] {
+        &self.events
+    }
+
+    fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn raw_log(&self, index: usize) -> &str {
+        self.raw_logs[index]
+    }
+
+    fn program_log(&self, index: usize) -> &str {
+        self.program_logs[index].msg
+    }
+
+    fn data_log_lens(&self, index: usize) -> Vec<usize> {
+        self.data_logs[index]
+            .fields()
+            .map(base64_decoded_len)
+            .collect()
+    }
+
+    fn return_data_len(&self) -> Option<usize> {
+        self.return_data.map(base64_decoded_len)
+    }
+}
+
+impl FrameView for ParsedStructuredLog {
+    fn program_id(&self) -> String {
+        self.program_id.to_string()
+    }
+
+    fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    fn instruction_name(&self) -> Option<&str> {
+        self.instruction_name.as_deref()
+    }
+
+    fn result(&self) -> FrameResult<'_> {
+        match &self.result {
+            ParsedProgramResult::Success => FrameResult::Success,
+            ParsedProgramResult::Err(err) => FrameResult::Err(err),
+            ParsedProgramResult::Incomplete => FrameResult::Incomplete,
+        }
+    }
+
+    fn compute_log(&self) -> Option<&ComputeUnits> {
+        self.compute_log.as_ref()
+    }
+
+    fn inclusive_consumed(&self) -> Option<u64> {
+        self.inclusive_consumed
+    }
+
+    fn self_consumed(&self) -> Option<u64> {
+        self.self_consumed
+    }
+
+    fn compute_checkpoints(&self) -> &[u64] {
+        &self.compute_checkpoints
+    }
+
+    fn cpi_logs(&self) -> &[Self] {
+        &self.cpi_logs
+    }
+
+    fn events(&self) -> &[FrameEvent] {
+        &self.events
+    }
+
+    fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn raw_log(&self, index: usize) -> &str {
+        &self.raw_logs[index]
+    }
+
+    fn program_log(&self, index: usize) -> &str {
+        &self.program_logs[index].msg
+    }
+
+    fn data_log_lens(&self, index: usize) -> Vec<usize> {
+        self.data_logs[index].data.iter().map(Vec::len).collect()
+    }
+
+    fn return_data_len(&self) -> Option<usize> {
+        self.return_data.as_ref().map(Vec::len)
+    }
+}
+
+/// The length of padded base64 once decoded, without decoding it
+fn base64_decoded_len(encoded: &str) -> usize {
+    let padding = encoded.bytes().rev().take_while(|b| *b == b'=').count();
+    (encoded.len() / 4 * 3).saturating_sub(padding)
+}
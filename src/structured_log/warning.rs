@@ -0,0 +1,73 @@
+use std::fmt::{self, Display};
+
+/// A structural problem that was recovered from while building structured logs
+///
+/// Warnings are produced in [`StructuredParseMode::Lenient`] and
+/// [`StructuredParseMode::BestEffort`]; in strict mode the same problems are
+/// returned as a [`LogParseError`]. The one exception is frames left open by a
+/// `Log truncated` marker, which are reported as [`IncompleteFrame`] warnings
+/// in every mode.
+///
+/// [`StructuredParseMode::Lenient`]: super::options::StructuredParseMode::Lenient
+/// [`StructuredParseMode::BestEffort`]: super::options::StructuredParseMode::BestEffort
+/// [`LogParseError`]: crate::LogParseError
+/// [`IncompleteFrame`]: StructuredParseWarning::IncompleteFrame
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StructuredParseWarning {
+    /// A frame was never closed and was finalized with an incomplete result,
+    /// reported at its invoke log
+    IncompleteFrame { index: usize, log: String },
+    /// A `success` log with no open frame was dropped
+    UnmatchedSuccess { index: usize, log: String },
+    /// A `failed` log with no open frame was dropped
+    UnmatchedFailed { index: usize, log: String },
+    /// A `success`/`failed` log did not close the innermost frame
+    MismatchedProgramId {
+        index: usize,
+        log: String,
+        expected: String,
+        found: String,
+    },
+    /// An invoke log was not exactly one level deeper than the innermost frame
+    DepthMismatch {
+        index: usize,
+        log: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for StructuredParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IncompleteFrame { index, log } => {
+                write!(f, "frame invoked at line {index} was never closed: {log}")
+            }
+            Self::UnmatchedSuccess { index, log } => {
+                write!(f, "dropped unmatched success log at line {index}: {log}")
+            }
+            Self::UnmatchedFailed { index, log } => {
+                write!(f, "dropped unmatched failed log at line {index}: {log}")
+            }
+            Self::MismatchedProgramId {
+                index,
+                log,
+                expected,
+                found,
+            } => write!(
+                f,
+                "mismatched program id at line {index}: expected {expected}, found {found}: {log}"
+            ),
+            Self::DepthMismatch {
+                index,
+                log,
+                expected,
+                found,
+            } => write!(
+                f,
+                "invoke depth mismatch at line {index}: expected {expected}, found {found}: {log}"
+            ),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+use pretty_assertions::assert_eq;
+use sol_log_parser::{structured_log::parsed::ParsedProgramResult, ParsedTransactionLog};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sol-log-parser"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn cli");
+    child
+        .stdin
+        .take()
+        .expect("missing stdin")
+        .write_all(stdin.as_bytes())
+        .expect("failed to write stdin");
+    child.wait_with_output().expect("failed to run cli")
+}
+
+#[test]
+fn cli_text_to_json() {
+    let input = "\
+Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]
+Program log: Instruction: Deposit
+Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 949 of 1400000 compute units
+Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success
+
+Program 11111111111111111111111111111111 invoke [1]
+Program 11111111111111111111111111111111 success
+";
+
+    let output = run(&["--format", "json"], input);
+    assert!(output.status.success());
+
+    let transactions: Vec<ParsedTransactionLog> =
+        serde_json::from_slice(&output.stdout).expect("invalid json output");
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(
+        transactions[0].frames[0].instruction_name.as_deref(),
+        Some("Deposit")
+    );
+    assert_eq!(
+        transactions[1].frames[0].result,
+        ParsedProgramResult::Success
+    );
+    assert!(transactions[1].success);
+}
+
+#[test]
+fn cli_json_input_modes() {
+    let input = r#"["Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]"]"#;
+
+    let output = run(&["--format", "ndjson"], input);
+    assert!(!output.status.success());
+
+    let output = run(&["--format", "ndjson", "--mode", "lenient"], input);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning"));
+
+    let stdout = String::from_utf8(output.stdout).expect("invalid utf8 output");
+    let transaction: ParsedTransactionLog =
+        serde_json::from_str(stdout.trim()).expect("invalid ndjson output");
+    assert_eq!(
+        transaction.frames[0].result,
+        ParsedProgramResult::Incomplete
+    );
+    assert_eq!(transaction.warnings.len(), 1);
+}
+
+#[test]
+fn cli_tree_orphans() {
+    let input = "\
+Program 11111111111111111111111111111111 invoke [1]
+Program 11111111111111111111111111111111 success
+Transfer: insufficient lamports 10, need 20
+Program 11111111111111111111111111111111 invoke [1]
+Program 11111111111111111111111111111111 failed: custom program error: 0x1
+";
+
+    let output = run(&["--format", "tree"], input);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("invalid utf8 output"),
+        "\
+11111111111111111111111111111111 success
+Transfer: insufficient lamports 10, need 20
+11111111111111111111111111111111 failed: custom program error: 0x1
+"
+    );
+}
+
+#[test]
+fn cli_diff() {
+    let baseline = "\
+Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]
+Program log: Instruction: Deposit
+Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 16000 of 200000 compute units
+Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success
+";
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let baseline_path = format!("{dir}/cli_diff_baseline.txt");
+    let candidate_path = format!("{dir}/cli_diff_candidate.txt");
+    fs::write(&baseline_path, baseline).expect("failed to write baseline");
+    fs::write(&candidate_path, baseline.replace("16000", "17000"))
+        .expect("failed to write candidate");
+
+    let output = run(&["diff", &baseline_path, &candidate_path], "");
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("invalid utf8 output"),
+        "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit): 16000 -> 17000 CU (+1000, +6.2%)\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("regressed"));
+
+    let output = run(
+        &[
+            "diff",
+            &baseline_path,
+            &candidate_path,
+            "--threshold-percent",
+            "10",
+        ],
+        "",
+    );
+    assert!(output.status.success());
+
+    // top-level arguments only apply without a subcommand
+    for args in [
+        &[&baseline_path, "diff", &baseline_path, &candidate_path][..],
+        &["--format", "json", "diff", &baseline_path, &candidate_path],
+    ] {
+        let output = run(args, "");
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+    }
+
+    // global arguments are accepted on either side of the subcommand
+    let output = run(
+        &["--mode", "lenient", "diff", &baseline_path, &baseline_path],
+        "",
+    );
+    assert!(output.status.success());
+}
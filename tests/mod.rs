@@ -1,12 +1,27 @@
 use pretty_assertions::assert_eq;
 use sol_log_parser::{
+    anchor_error::AnchorErrorSource,
     parsed_log::{
-        ParsedCuLog, ParsedDataLog, ParsedFailedLog, ParsedInvokeLog, ParsedProgramLog,
-        ParsedSuccessLog,
+        ParsedConsumptionLog, ParsedCuLog, ParsedDataLog, ParsedFailedLog, ParsedInvokeLog,
+        ParsedProgramLog, ParsedSuccessLog, ParsedTruncatedLog,
     },
-    raw_log::{RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawProgramLog, RawSuccessLog},
-    structured_log::{parsed::ParsedProgramResult, ComputeUnits},
-    LogParseError, ParsedLog, ParsedStructuredLog, RawLog,
+    raw_log::{
+        RawConsumptionLog, RawCuLog, RawDataLog, RawFailedLog, RawInvokeLog, RawProgramLog,
+        RawSuccessLog, RawTruncatedLog,
+    },
+    structured_log::{
+        budget::BudgetRecommendation,
+        call_graph::CallStats,
+        compute_stats::ComputeUnitStats,
+        parsed::{ParsedOrphanLog, ParsedProgramResult},
+        raw::{RawOrphanLog, RawProgramResult},
+        ComputeUnits, FrameEvent,
+    },
+    AnchorError, BudgetAnalyzer, CallGraph, ChromeTrace, ComputeDiff, ComputeProfile, ComputeStats,
+    FoldedStacks, GraphFormatter, LogParseError, ParsedInstructionError, ParsedLog,
+    ParsedStructuredLog, ParsedTransactionLog, ProgramPanic, RawLog, RawStructuredLog,
+    RawTransactionLog, RegressionThreshold, StructuredParseOptions, StructuredParseWarning,
+    TreeFormatter,
 };
 use solana_pubkey::Pubkey;
 
@@ -78,11 +93,62 @@ pub fn failed_log() {
         ParsedLog::Failed(ParsedFailedLog {
             raw: "Program 11111111111111111111111111111111 failed: insufficient funds".into(),
             program_id: Pubkey::from_str_const("111111111111111111111111111111111"),
-            err: String::from("insufficient funds")
+            err: ParsedInstructionError::Unknown(String::from("insufficient funds"))
         })
     )
 }
 
+#[test]
+pub fn instruction_error() {
+    let cases = [
+        (
+            "custom program error: 0x1770",
+            ParsedInstructionError::Custom(6000),
+        ),
+        (
+            "invalid account data for instruction",
+            ParsedInstructionError::InvalidAccountData,
+        ),
+        (
+            "Computational budget exceeded",
+            ParsedInstructionError::ComputationalBudgetExceeded,
+        ),
+        (
+            "Program failed to complete",
+            ParsedInstructionError::ProgramFailedToComplete,
+        ),
+        (
+            "Access violation in stack frame 5 at address 0x200005ff8 of size 8",
+            ParsedInstructionError::AccessViolation {
+                section: "stack frame 5".into(),
+                address: 0x200005ff8,
+                size: 8,
+            },
+        ),
+        (
+            "Failed to serialize or deserialize account data: Unknown",
+            ParsedInstructionError::BorshIoError("Unknown".into()),
+        ),
+        (
+            "SBF program panicked",
+            ParsedInstructionError::Unknown("SBF program panicked".into()),
+        ),
+    ];
+
+    for (err, expected) in cases {
+        let parsed = ParsedInstructionError::parse(err);
+        assert_eq!(parsed, expected);
+        assert_eq!(parsed.to_string(), err);
+    }
+
+    let log = "Program 11111111111111111111111111111111 failed: custom program error: 0x1";
+    let parsed_log = ParsedLog::from_raw(&RawLog::parse(log)).expect("failed to parse log");
+    let ParsedLog::Failed(failed_log) = parsed_log else {
+        panic!("expected a failed log, got {parsed_log:?}");
+    };
+    assert_eq!(failed_log.err, ParsedInstructionError::Custom(1));
+}
+
 #[test]
 pub fn program_log() {
     let log = "Program log: Hello from inside the program";
@@ -124,11 +190,38 @@ pub fn data_log() {
         parsed_log,
         ParsedLog::Data(ParsedDataLog {
             raw: "Program data: aGVsbG8gc29sYW5h".into(),
-            data: b"hello solana".to_vec()
+            data: vec![b"hello solana".to_vec()]
         })
     )
 }
 
+#[test]
+pub fn multi_field_data_log() {
+    let log = "Program data: aGVsbG8= c29sYW5h AQID";
+
+    let raw_log = RawLog::parse(log);
+    let RawLog::Data(raw_data_log) = &raw_log else {
+        panic!("expected a data log, got {raw_log:?}");
+    };
+    assert_eq!(
+        raw_data_log.fields().collect::<Vec<_>>(),
+        vec!["aGVsbG8=", "c29sYW5h", "AQID"]
+    );
+
+    let parsed_log = ParsedLog::from_raw(&raw_log).expect("failed to parse log");
+    let ParsedLog::Data(parsed_data_log) = parsed_log else {
+        panic!("expected a data log, got {parsed_log:?}");
+    };
+    assert_eq!(
+        parsed_data_log.data,
+        vec![b"hello".to_vec(), b"solana".to_vec(), vec![1, 2, 3]]
+    );
+    assert_eq!(
+        parsed_data_log.flattened(),
+        b"hellosolana\x01\x02\x03".to_vec()
+    );
+}
+
 #[test]
 pub fn cu_log() {
     let log = "Program 11111111111111111111111111111111 consumed 1820 of 200000 compute units";
@@ -192,6 +285,7 @@ fn structured_log() {
         ParsedStructuredLog {
             program_id: Pubkey::from_str_const("D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns"),
             depth: 1,
+            instruction_name: None,
             result: ParsedProgramResult::Success,
             program_logs: vec![],
             data_logs: vec![],
@@ -200,9 +294,13 @@ fn structured_log() {
                 consumed: 8388,
                 budget: 1400000
             }),
+            inclusive_consumed: Some(8388),
+            self_consumed: Some(3657),
+            compute_checkpoints: vec![],
             cpi_logs: vec![ParsedStructuredLog {
                 program_id: Pubkey::from_str_const("11111111111111111111111111111111"),
                 depth: 2,
+                instruction_name: Some(String::from("CreateAccount")),
                 result: ParsedProgramResult::Success,
                 program_logs: vec![ParsedProgramLog {
                     raw: "Program log: Instruction: CreateAccount".into(),
@@ -214,19 +312,1142 @@ fn structured_log() {
                     consumed: 4731,
                     budget: 1396590
                 }),
+                inclusive_consumed: Some(4731),
+                self_consumed: Some(4731),
+                compute_checkpoints: vec![],
                 cpi_logs: vec![],
+                events: vec![FrameEvent::Log(0), FrameEvent::Compute],
                 raw_logs: vec![
                     "Program 11111111111111111111111111111111 invoke [2]".into(),
                     "Program log: Instruction: CreateAccount".into(),
                     "Program 11111111111111111111111111111111 consumed 4731 of 1396590 compute units".into(),
                     "Program 11111111111111111111111111111111 success".into(),
-                ]
+                ],
+                truncated: false,
+                anchor_error: None,
+                panic: None,
             }],
+            events: vec![FrameEvent::Cpi(0), FrameEvent::Compute],
             raw_logs: vec![
                 "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]".into(),
                 "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 8388 of 1400000 compute units".into(),
                 "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success".into(),
-            ]
+            ],
+            truncated: false,
+            anchor_error: None,
+            panic: None,
         }
     )
 }
+
+#[test]
+fn structured_log_compute_units() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 12000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+
+    let structured = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+    let [caller] = &structured[..] else {
+        panic!("expected a single frame");
+    };
+    assert_eq!(caller.inclusive_consumed, Some(12000));
+    // the system program logs no consumption, so its units stay with the caller
+    assert_eq!(caller.self_consumed, Some(7355));
+
+    let [builtin, token] = &caller.cpi_logs[..] else {
+        panic!("expected two cpi logs");
+    };
+    assert_eq!(builtin.inclusive_consumed, None);
+    assert_eq!(builtin.self_consumed, None);
+    assert_eq!(token.inclusive_consumed, Some(4645));
+    assert_eq!(token.self_consumed, Some(4645));
+}
+
+#[test]
+fn structured_log_errors() {
+    let unmatched = ["Program 11111111111111111111111111111111 success"];
+    let err = RawStructuredLog::from_raw_logs(unmatched.into_iter().map(RawLog::parse).collect())
+        .expect_err("unmatched success should fail");
+    assert!(matches!(
+        err,
+        LogParseError::UnmatchedSuccess { index: 0, ref log }
+            if log == "Program 11111111111111111111111111111111 success"
+    ));
+
+    let mismatched = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 failed: custom program error: 0x1",
+    ];
+    let err = RawStructuredLog::from_raw_logs(mismatched.into_iter().map(RawLog::parse).collect())
+        .expect_err("mismatched program id should fail");
+    assert!(matches!(
+        err,
+        LogParseError::MismatchedProgramId { index: 1, ref expected, ref found, .. }
+            if expected == "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns"
+                && found == "11111111111111111111111111111111"
+    ));
+
+    let unbalanced = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+    ];
+    let err = RawStructuredLog::from_raw_logs(unbalanced.into_iter().map(RawLog::parse).collect())
+        .expect_err("unbalanced stack should fail");
+    assert!(matches!(
+        err,
+        LogParseError::UnbalancedStack {
+            index: 1,
+            open: 2,
+            ..
+        }
+    ));
+
+    let skipped_depth = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [3]",
+    ];
+    let err =
+        RawStructuredLog::from_raw_logs(skipped_depth.into_iter().map(RawLog::parse).collect())
+            .expect_err("depth mismatch should fail");
+    assert!(matches!(
+        err,
+        LogParseError::DepthMismatch {
+            index: 1,
+            expected: 2,
+            found: 3,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn structured_log_parse_modes() {
+    let truncated = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program log: still running",
+    ];
+    let raw_logs = || truncated.into_iter().map(RawLog::parse).collect::<Vec<_>>();
+
+    RawStructuredLog::from_raw_logs_with_options(raw_logs(), StructuredParseOptions::strict())
+        .expect_err("strict mode should reject open frames");
+
+    let (structured, warnings) =
+        RawStructuredLog::from_raw_logs_with_options(raw_logs(), StructuredParseOptions::lenient())
+            .expect("lenient mode should close open frames");
+    assert_eq!(structured.len(), 1);
+    assert_eq!(structured[0].result, RawProgramResult::Incomplete);
+    assert_eq!(structured[0].cpi_logs[0].result, RawProgramResult::Success);
+    assert_eq!(structured[0].raw_logs.len(), 2);
+    assert_eq!(
+        warnings,
+        vec![StructuredParseWarning::IncompleteFrame {
+            index: 0,
+            log: "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]".into()
+        }]
+    );
+
+    let mismatched = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+        "Program 11111111111111111111111111111111 success",
+    ];
+    let raw_logs = || {
+        mismatched
+            .into_iter()
+            .map(RawLog::parse)
+            .collect::<Vec<_>>()
+    };
+
+    RawStructuredLog::from_raw_logs_with_options(raw_logs(), StructuredParseOptions::lenient())
+        .expect_err("lenient mode should reject mismatched program ids");
+
+    let (structured, warnings) = RawStructuredLog::from_raw_logs_with_options(
+        raw_logs(),
+        StructuredParseOptions::best_effort(),
+    )
+    .expect("best-effort mode should recover");
+    assert_eq!(structured.len(), 1);
+    assert_eq!(structured[0].result, RawProgramResult::Success);
+    assert_eq!(
+        structured[0].cpi_logs[0].result,
+        RawProgramResult::Incomplete
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            StructuredParseWarning::MismatchedProgramId {
+                index: 2,
+                log: "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success".into(),
+                expected: "11111111111111111111111111111111".into(),
+                found: "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns".into(),
+            },
+            StructuredParseWarning::IncompleteFrame {
+                index: 1,
+                log: "Program 11111111111111111111111111111111 invoke [2]".into(),
+            },
+            StructuredParseWarning::UnmatchedSuccess {
+                index: 3,
+                log: "Program 11111111111111111111111111111111 success".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn structured_log_depth() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [3]",
+        "Program 11111111111111111111111111111111 success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [2]",
+        "Program log: lost its closing logs",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+    let raw_logs = || logs.into_iter().map(RawLog::parse).collect::<Vec<_>>();
+
+    RawStructuredLog::from_raw_logs_with_options(raw_logs(), StructuredParseOptions::strict())
+        .expect_err("strict mode should reject depth mismatches");
+
+    let (structured, warnings) =
+        RawStructuredLog::from_raw_logs_with_options(raw_logs(), StructuredParseOptions::lenient())
+            .expect("lenient mode should accept depth mismatches");
+    assert_eq!(structured.len(), 1);
+    assert_eq!(structured[0].result, RawProgramResult::Success);
+
+    let [skipped, unclosed, sibling] = &structured[0].cpi_logs[..] else {
+        panic!("expected three cpi logs");
+    };
+    assert_eq!(skipped.depth, 3);
+    assert_eq!(skipped.result, RawProgramResult::Success);
+    assert_eq!(unclosed.result, RawProgramResult::Incomplete);
+    assert_eq!(sibling.depth, 2);
+    assert_eq!(sibling.result, RawProgramResult::Success);
+    assert_eq!(
+        warnings,
+        vec![
+            StructuredParseWarning::DepthMismatch {
+                index: 1,
+                log: "Program 11111111111111111111111111111111 invoke [3]".into(),
+                expected: 2,
+                found: 3,
+            },
+            StructuredParseWarning::DepthMismatch {
+                index: 5,
+                log: "Program 11111111111111111111111111111111 invoke [2]".into(),
+                expected: 3,
+                found: 2,
+            },
+            StructuredParseWarning::IncompleteFrame {
+                index: 3,
+                log: "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [2]".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn structured_log_depth_drift() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [3]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [4]",
+        "Program 11111111111111111111111111111111 invoke [5]",
+        "Program 11111111111111111111111111111111 success",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program 11111111111111111111111111111111 success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+
+    // only the drifted invoke is reported, its subtree nests normally under it
+    let (structured, warnings) = RawStructuredLog::from_raw_logs_with_options(
+        logs.into_iter().map(RawLog::parse).collect(),
+        StructuredParseOptions::lenient(),
+    )
+    .expect("lenient mode should accept depth mismatches");
+    assert_eq!(
+        warnings,
+        vec![StructuredParseWarning::DepthMismatch {
+            index: 1,
+            log: "Program 11111111111111111111111111111111 invoke [3]".into(),
+            expected: 2,
+            found: 3,
+        }]
+    );
+
+    let [root] = &structured[..] else {
+        panic!("expected one top-level frame");
+    };
+    assert_eq!(root.result, RawProgramResult::Success);
+    let [drifted] = &root.cpi_logs[..] else {
+        panic!("expected one cpi log");
+    };
+    assert_eq!(drifted.depth, 3);
+    let [token] = &drifted.cpi_logs[..] else {
+        panic!("expected one nested cpi log");
+    };
+    assert_eq!(token.depth, 4);
+    assert_eq!(token.cpi_logs.len(), 1);
+    assert_eq!(token.cpi_logs[0].depth, 5);
+    assert_eq!(token.cpi_logs[0].result, RawProgramResult::Success);
+}
+
+#[test]
+fn truncated_log() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Log truncated",
+    ];
+
+    let raw_logs: Vec<_> = logs.into_iter().map(RawLog::parse).collect();
+    assert_eq!(
+        raw_logs[4],
+        RawLog::Truncated(RawTruncatedLog {
+            raw: "Log truncated"
+        })
+    );
+
+    let parsed_logs = raw_logs
+        .iter()
+        .map(ParsedLog::from_raw)
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("Failed to parsed logs");
+    assert_eq!(
+        parsed_logs[4],
+        ParsedLog::Truncated(ParsedTruncatedLog {
+            raw: "Log truncated".into()
+        })
+    );
+
+    // the truncation marker explains the open frames, so strict mode accepts
+    // them and still reports them as warnings
+    let (structured, warnings) = ParsedStructuredLog::from_parsed_logs_with_options(
+        parsed_logs,
+        StructuredParseOptions::strict(),
+    )
+    .expect("Failed to parse structured logs");
+    assert_eq!(
+        warnings,
+        vec![
+            StructuredParseWarning::IncompleteFrame {
+                index: 3,
+                log: "Program 11111111111111111111111111111111 invoke [2]".into(),
+            },
+            StructuredParseWarning::IncompleteFrame {
+                index: 0,
+                log: "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]".into(),
+            },
+        ]
+    );
+    assert_eq!(structured.len(), 1);
+    assert!(structured[0].truncated);
+    assert_eq!(structured[0].result, ParsedProgramResult::Incomplete);
+
+    let [completed, open] = &structured[0].cpi_logs[..] else {
+        panic!("expected two cpi logs");
+    };
+    assert!(!completed.truncated);
+    assert_eq!(completed.result, ParsedProgramResult::Success);
+    assert!(open.truncated);
+    assert_eq!(open.result, ParsedProgramResult::Incomplete);
+    assert_eq!(
+        open.raw_logs.last().map(String::as_str),
+        Some("Log truncated")
+    );
+}
+
+#[test]
+fn structured_log_events() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: before",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program data: aGVsbG8gc29sYW5h",
+        "Program log: after",
+        "Program return: D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns AQ==",
+        "something else",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 949 of 1400000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+
+    let structured = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+
+    let frame = &structured[0];
+    assert_eq!(
+        frame.events,
+        vec![
+            FrameEvent::Log(0),
+            FrameEvent::Cpi(0),
+            FrameEvent::Data(0),
+            FrameEvent::Log(1),
+            FrameEvent::Return,
+            FrameEvent::Other(5),
+            FrameEvent::Compute,
+        ]
+    );
+    assert_eq!(frame.program_logs[1].msg, "after");
+    assert_eq!(frame.instruction_name, None);
+    assert_eq!(frame.raw_logs[5], "something else");
+}
+
+#[test]
+fn anchor_error() {
+    let thrown = "AnchorError thrown in programs/vault/src/lib.rs:42. Error Code: InsufficientBalance. Error Number: 6001. Error Message: Not enough funds.";
+    assert_eq!(
+        AnchorError::parse(thrown),
+        Some(AnchorError {
+            error_code: "InsufficientBalance".into(),
+            error_number: 6001,
+            error_message: "Not enough funds".into(),
+            source: Some(AnchorErrorSource {
+                file: "programs/vault/src/lib.rs".into(),
+                line: 42,
+            }),
+            account: None,
+            left: None,
+            right: None,
+        })
+    );
+    assert_eq!(AnchorError::parse("Instruction: Deposit"), None);
+
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Withdraw",
+        "Program log: AnchorError caused by account: vault. Error Code: ConstraintSeeds. Error Number: 2006. Error Message: A seeds constraint was violated.",
+        "Program log: Left:",
+        "Program log: 9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        "Program log: Right:",
+        "Program log: 4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 5000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: custom program error: 0x7d6",
+    ];
+
+    let parsed_logs = logs
+        .into_iter()
+        .map(RawLog::parse)
+        .map(|raw| ParsedLog::from_raw(&raw))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("Failed to parsed logs");
+    let structured = ParsedStructuredLog::from_parsed_logs(parsed_logs)
+        .expect("Failed to parse structured logs");
+
+    assert_eq!(
+        structured[0].result,
+        ParsedProgramResult::Err(ParsedInstructionError::Custom(2006))
+    );
+    assert_eq!(structured[0].instruction_name.as_deref(), Some("Withdraw"));
+    assert_eq!(
+        structured[0].anchor_error,
+        Some(AnchorError {
+            error_code: "ConstraintSeeds".into(),
+            error_number: 2006,
+            error_message: "A seeds constraint was violated".into(),
+            source: None,
+            account: Some("vault".into()),
+            left: Some("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".into()),
+            right: Some("4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T".into()),
+        })
+    );
+}
+
+#[test]
+fn program_panic() {
+    assert_eq!(
+        ProgramPanic::parse("panicked at 'attempt to divide by zero', src/lib.rs:10:5"),
+        Some(ProgramPanic {
+            message: "attempt to divide by zero".into(),
+            file: "src/lib.rs".into(),
+            line: 10,
+            column: 5,
+        })
+    );
+    assert_eq!(
+        ProgramPanic::parse("panicked at src/lib.rs:10:5:\nattempt to divide by zero"),
+        Some(ProgramPanic {
+            message: "attempt to divide by zero".into(),
+            file: "src/lib.rs".into(),
+            line: 10,
+            column: 5,
+        })
+    );
+    assert_eq!(ProgramPanic::parse("panicked at nowhere"), None);
+
+    // the newer format split across lines, as when logs are read line by line
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: panicked at programs/vault/src/math.rs:21:9:",
+        "attempt to subtract with overflow",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 1210 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: SBF program panicked",
+    ];
+
+    let parsed_logs = logs
+        .into_iter()
+        .map(RawLog::parse)
+        .map(|raw| ParsedLog::from_raw(&raw))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("Failed to parsed logs");
+    let structured = ParsedStructuredLog::from_parsed_logs(parsed_logs)
+        .expect("Failed to parse structured logs");
+
+    assert_eq!(
+        structured[0].panic,
+        Some(ProgramPanic {
+            message: "attempt to subtract with overflow".into(),
+            file: "programs/vault/src/math.rs".into(),
+            line: 21,
+            column: 9,
+        })
+    );
+}
+
+#[test]
+fn consumption_log() {
+    let log = "Program consumption: 198765 units remaining";
+
+    let raw_log = RawLog::parse(log);
+    assert_eq!(
+        raw_log,
+        RawLog::Consumption(RawConsumptionLog {
+            raw: "Program consumption: 198765 units remaining",
+            remaining: 198765,
+        })
+    );
+
+    let parsed_log = ParsedLog::from_raw(&raw_log).expect("failed to parse log");
+    assert_eq!(
+        parsed_log,
+        ParsedLog::Consumption(ParsedConsumptionLog {
+            raw: "Program consumption: 198765 units remaining".into(),
+            remaining: 198765,
+        })
+    );
+
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program consumption: 199500 units remaining",
+        "Program log: expensive work",
+        "Program consumption: 190000 units remaining",
+        "Program log: cheap work",
+        "Program consumption: 189900 units remaining",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 10200 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+    let structured = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+
+    assert_eq!(
+        structured[0].compute_checkpoints,
+        vec![199500, 190000, 189900]
+    );
+    assert_eq!(structured[0].checkpoint_deltas(), vec![9500, 100]);
+    assert_eq!(
+        structured[0].events[..4],
+        [
+            FrameEvent::Checkpoint(0),
+            FrameEvent::Log(0),
+            FrameEvent::Checkpoint(1),
+            FrameEvent::Log(1),
+        ]
+    );
+}
+
+#[test]
+fn structured_log_tree() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Deposit",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program log: nested",
+        "Program 11111111111111111111111111111111 success",
+        "Program data: aGVsbG8= c29sYW5h",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 8388 of 1400000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: custom program error: 0x1770",
+    ];
+
+    let raw = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+    let parsed_logs = logs
+        .into_iter()
+        .map(RawLog::parse)
+        .map(|raw| ParsedLog::from_raw(&raw))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("Failed to parsed logs");
+    let parsed = ParsedStructuredLog::from_parsed_logs(parsed_logs)
+        .expect("Failed to parse structured logs");
+
+    let expected = "\
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit) failed: custom program error: 0x1770 [8388 of 1400000 CU]
+├── log: Instruction: Deposit
+├── 11111111111111111111111111111111 success
+│   └── log: nested
+└── data: 5 + 6 bytes";
+    assert_eq!(raw[0].to_string(), expected);
+    assert_eq!(parsed[0].to_string(), expected);
+
+    let formatter = TreeFormatter::new()
+        .ascii(true)
+        .data_logs(false)
+        .compute_units(false);
+    assert_eq!(
+        formatter.display(&parsed[0]).to_string(),
+        "\
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit) failed: custom program error: 0x1770
+|-- log: Instruction: Deposit
+`-- 11111111111111111111111111111111 success
+    `-- log: nested"
+    );
+}
+
+#[test]
+fn transaction_log() {
+    let logs = [
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+        "Program ComputeBudget111111111111111111111111111111 success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 949 of 1399850 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+        "Transfer: insufficient lamports 10, need 20",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 300 of 1398901 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: custom program error: 0x1770",
+    ];
+
+    let raw = RawTransactionLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse transaction logs");
+    assert_eq!(raw.frames.len(), 3);
+    assert_eq!(
+        raw.orphans,
+        vec![RawOrphanLog {
+            index: 5,
+            position: 2,
+            log: "Transfer: insufficient lamports 10, need 20",
+        }]
+    );
+    assert!(!raw.success);
+    assert_eq!(raw.failed_instruction, Some(2));
+    assert_eq!(raw.compute_units_consumed, 1249);
+    assert!(!raw.truncated);
+
+    let parsed_logs = logs
+        .into_iter()
+        .map(RawLog::parse)
+        .map(|raw| ParsedLog::from_raw(&raw))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("Failed to parsed logs");
+    let parsed = ParsedTransactionLog::from_parsed_logs(parsed_logs)
+        .expect("Failed to parse transaction logs");
+    assert_eq!(parsed.frames.len(), 3);
+    assert_eq!(
+        parsed.orphans,
+        vec![ParsedOrphanLog {
+            index: 5,
+            position: 2,
+            log: "Transfer: insufficient lamports 10, need 20".into(),
+        }]
+    );
+    assert!(!parsed.success);
+    assert_eq!(parsed.failed_instruction, Some(2));
+    assert_eq!(parsed.compute_units_consumed, 1249);
+    assert_eq!(parsed.signature, None);
+}
+
+#[test]
+fn truncated_transaction_log() {
+    let logs = [
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+        "Program ComputeBudget111111111111111111111111111111 success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Log truncated",
+    ];
+
+    let raw = RawTransactionLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse transaction logs");
+    assert_eq!(raw.frames.len(), 2);
+    assert!(raw.truncated);
+    assert!(!raw.success);
+    assert_eq!(raw.failed_instruction, None);
+
+    // an incomplete frame is not a success even without the truncation marker
+    let parsed_logs = logs[..4]
+        .iter()
+        .map(|log| ParsedLog::from_raw(&RawLog::parse(log)))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("Failed to parsed logs");
+    let parsed = ParsedTransactionLog::from_parsed_logs_with_options(
+        parsed_logs,
+        StructuredParseOptions::lenient(),
+    )
+    .expect("Failed to parse transaction logs");
+    assert_eq!(parsed.frames.len(), 2);
+    assert!(!parsed.truncated);
+    assert!(!parsed.success);
+    assert_eq!(parsed.failed_instruction, None);
+}
+
+#[test]
+fn folded_stacks() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Deposit",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 12000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 300 of 188000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+
+    let structured = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+
+    let mut folded = FoldedStacks::new();
+    folded.add_all(&structured);
+    assert_eq!(
+        folded.to_string(),
+        "\
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns 7655
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns;TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA 4645
+"
+    );
+
+    let mut folded = FoldedStacks::new().instruction_names(true);
+    folded.add_all(&structured);
+    folded.add_all(&structured);
+    assert_eq!(
+        folded.to_string(),
+        "\
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns 600
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit) 14710
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit);TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA 9290
+"
+    );
+}
+
+#[test]
+fn chrome_trace() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Deposit",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program log: \"done\"",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 16000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+        "Program 11111111111111111111111111111111 invoke [1]",
+        "Program 11111111111111111111111111111111 success",
+    ];
+
+    let structured = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+
+    let mut trace = ChromeTrace::new();
+    trace.add(&structured);
+    let trace: serde_json::Value =
+        serde_json::from_str(&trace.to_string()).expect("invalid trace json");
+
+    let events = trace["traceEvents"]
+        .as_array()
+        .expect("missing trace events");
+    let summary: Vec<_> = events
+        .iter()
+        .map(|event| {
+            (
+                event["ph"].as_str().unwrap_or_default(),
+                event["name"].as_str().unwrap_or_default(),
+                event["ts"].as_u64(),
+                event["dur"].as_u64(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("M", "thread_name", None, None),
+            (
+                "X",
+                "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit)",
+                Some(0),
+                Some(16000)
+            ),
+            ("i", "Instruction: Deposit", Some(0), None),
+            (
+                "X",
+                "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                Some(10000),
+                Some(4645)
+            ),
+            ("i", "\"done\"", Some(14645), None),
+            (
+                "X",
+                "11111111111111111111111111111111",
+                Some(16000),
+                Some(0)
+            ),
+        ]
+    );
+    assert_eq!(events[1]["args"]["self_consumed"], 11355);
+    assert_eq!(events[5]["args"]["result"], "success");
+}
+
+#[test]
+fn call_tree_graph() {
+    let logs = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Deposit",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 failed: custom program error: 0x1",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 16000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: custom program error: 0x1",
+    ];
+
+    let structured = RawStructuredLog::from_raw_logs(logs.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+
+    let graph = GraphFormatter::new();
+    assert_eq!(
+        graph.dot(&structured).to_string(),
+        r##"digraph cpi {
+    node [shape=box];
+    n0 [label="D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns\nDeposit\n16000 CU\nfailed: custom program error: 0x1", style=filled, fillcolor="#f8d7da", color=red];
+    n1 [label="Token Program\n4645 CU\nsuccess"];
+    n2 [label="System Program\nfailed: custom program error: 0x1", style=filled, fillcolor="#f8d7da", color=red];
+    n0 -> n1;
+    n0 -> n2;
+}"##
+    );
+
+    let graph = GraphFormatter::new()
+        .known_names(false)
+        .compute_units(false);
+    assert_eq!(
+        graph.mermaid(&structured).to_string(),
+        "\
+flowchart TD
+    n0[\"D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns<br/>Deposit<br/>failed: custom program error: 0x1\"]
+    n1[\"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA<br/>success\"]
+    n2[\"11111111111111111111111111111111<br/>failed: custom program error: 0x1\"]
+    n0 --> n1
+    n0 --> n2
+    classDef failed fill:#f8d7da,stroke:#dc3545
+    class n0,n2 failed"
+    );
+}
+
+#[test]
+fn call_graph() {
+    let first = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 16000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+    let second = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 3000 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 14000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: custom program error: 0x1",
+    ];
+
+    let first = RawStructuredLog::from_raw_logs(first.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+    let second = RawStructuredLog::from_raw_logs(second.into_iter().map(RawLog::parse).collect())
+        .expect("Failed to parse structured logs");
+
+    let mut graph = CallGraph::new();
+    graph.add_all(&first);
+    let mut shard = CallGraph::new();
+    shard.add_all(&second);
+    graph.merge(&shard);
+
+    assert_eq!(
+        graph.edge(
+            "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns",
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        ),
+        Some(&CallStats {
+            calls: 2,
+            consumed: 7645,
+            failures: 1,
+        })
+    );
+
+    assert_eq!(
+        graph.dot().to_string(),
+        r#"digraph calls {
+    node [shape=box];
+    transaction [shape=ellipse];
+    "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns" [label="D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns"];
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" [label="Token Program"];
+    transaction -> "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns" [label="2 calls\n30000 CU\n1 failed", color=red];
+    "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns" -> "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" [label="2 calls\n7645 CU\n1 failed", color=red];
+}"#
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&graph.json().to_string()).expect("invalid call graph json");
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "roots": [{
+                "program_id": "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns",
+                "calls": 2,
+                "consumed": 30000,
+                "failures": 1,
+            }],
+            "edges": [{
+                "caller": "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns",
+                "callee": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                "calls": 2,
+                "consumed": 7645,
+                "failures": 1,
+            }],
+        })
+    );
+}
+
+#[test]
+fn compute_stats() {
+    let transaction = |consumed: u64, failed: bool| {
+        let result = if failed {
+            "failed: custom program error: 0x1"
+        } else {
+            "success"
+        };
+        let logs = [
+            "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]".to_string(),
+            "Program log: Instruction: Deposit".to_string(),
+            "Program 11111111111111111111111111111111 invoke [2]".to_string(),
+            "Program 11111111111111111111111111111111 success".to_string(),
+            format!("Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed {consumed} of 200000 compute units"),
+            format!("Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns {result}"),
+        ];
+        let parsed_logs = logs
+            .iter()
+            .map(|log| ParsedLog::from_raw(&RawLog::parse(log)))
+            .collect::<Result<Vec<_>, LogParseError>>()
+            .expect("Failed to parsed logs");
+        ParsedStructuredLog::from_parsed_logs(parsed_logs).expect("Failed to parse structured logs")
+    };
+
+    let mut stats = ComputeStats::new();
+    let mut shards = [ComputeStats::new(), ComputeStats::new()];
+    for consumed in 1..=100 {
+        let frames = transaction(consumed, consumed % 10 == 0);
+        stats.add_all(&frames);
+        shards[consumed as usize % 2].add_all(&frames);
+    }
+
+    let program = stats
+        .program("D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns")
+        .expect("missing program stats");
+    assert_eq!(program.invocations(), 100);
+    assert_eq!(program.samples(), 100);
+    assert_eq!(program.min(), Some(1));
+    assert_eq!(program.max(), Some(100));
+    assert_eq!(program.mean(), Some(50.5));
+    assert_eq!(program.p50(), Some(50));
+    assert_eq!(program.p90(), Some(90));
+    assert_eq!(program.p99(), Some(99));
+    assert_eq!(program.failure_rate(), 0.1);
+    assert_eq!(program.utilization(), Some(5050.0 / 20_000_000.0));
+    assert_eq!(
+        stats.instruction("D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns", "Deposit"),
+        Some(program)
+    );
+
+    // the system program logs no consumption, so only its invocations count
+    let system = stats
+        .program("11111111111111111111111111111111")
+        .expect("missing program stats");
+    assert_eq!(system.invocations(), 100);
+    assert_eq!(system.samples(), 0);
+    assert_eq!(system.p50(), None);
+
+    let [mut merged, shard] = shards;
+    merged.merge(&shard);
+    assert_eq!(merged, stats);
+
+    let mut large = ComputeUnitStats::default();
+    for consumed in [10_000, 150_000, 1_399_999] {
+        large.record(consumed, 1_400_000);
+    }
+    assert_eq!(large.p50(), Some(150_527));
+    assert_eq!(large.p99(), Some(1_399_999));
+}
+
+#[test]
+fn budget_recommendation() {
+    let mut analyzer = BudgetAnalyzer::new();
+    assert_eq!(analyzer.recommend(), None);
+    for consumed in (1..=100).map(|consumed| consumed * 1000) {
+        let logs = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+            "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+            "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]".to_string(),
+            format!("Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed {consumed} of 1399850 compute units"),
+            "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success".to_string(),
+        ];
+        let frames =
+            RawStructuredLog::from_raw_logs(logs.iter().map(|log| RawLog::parse(log)).collect())
+                .expect("Failed to parse structured logs");
+        analyzer.add(&frames);
+    }
+
+    let recommendation = analyzer.recommend().expect("missing recommendation");
+    assert_eq!(
+        recommendation,
+        BudgetRecommendation {
+            unit_limit: 109_260,
+            transactions: 100,
+            quantile_consumed: 99_327,
+            max_consumed: 100_000,
+            total_consumed: 5_050_000,
+            total_requested: 139_985_000,
+            total_wasted: 134_935_000,
+        }
+    );
+    assert!(recommendation.wasted_ratio() > 0.96);
+
+    let mut analyzer = BudgetAnalyzer::new().quantile(1.0).margin(2.0);
+    analyzer.record(1_000_000, 1_400_000);
+    assert_eq!(
+        analyzer
+            .recommend()
+            .map(|recommendation| recommendation.unit_limit),
+        Some(1_400_000)
+    );
+}
+
+#[test]
+fn budget_bounds() {
+    let mut analyzer = BudgetAnalyzer::new().quantile(0.0).margin(1.0);
+    analyzer.record(1000, 200_000);
+    assert_eq!(
+        analyzer
+            .recommend()
+            .map(|recommendation| recommendation.unit_limit),
+        Some(1000)
+    );
+}
+
+#[test]
+#[should_panic(expected = "quantile must be between 0.0 and 1.0")]
+fn budget_quantile_out_of_range() {
+    let _ = BudgetAnalyzer::new().quantile(1.5);
+}
+
+#[test]
+#[should_panic(expected = "margin must be at least 1.0")]
+fn budget_margin_nan() {
+    let _ = BudgetAnalyzer::new().margin(f64::NAN);
+}
+
+#[test]
+#[should_panic(expected = "quantile must be between 0.0 and 1.0")]
+fn budget_quantile_field_out_of_range() {
+    let mut analyzer = BudgetAnalyzer::new();
+    analyzer.quantile = 7.0;
+    analyzer.record(100_000, 200_000);
+    let _ = analyzer.recommend();
+}
+
+#[test]
+#[should_panic(expected = "margin must be at least 1.0")]
+fn budget_margin_field_out_of_range() {
+    let mut analyzer = BudgetAnalyzer::new();
+    analyzer.margin = 0.1;
+    analyzer.record(100_000, 200_000);
+    let _ = analyzer.recommend();
+}
+
+#[test]
+#[should_panic(expected = "margin must be at least 1.0")]
+fn budget_margin_field_nan() {
+    let mut analyzer = BudgetAnalyzer::new();
+    analyzer.margin = f64::NAN;
+    analyzer.record(100_000, 200_000);
+    let _ = analyzer.recommend();
+}
+
+#[test]
+fn compute_diff() {
+    let baseline = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Deposit",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program 11111111111111111111111111111111 invoke [2]",
+        "Program 11111111111111111111111111111111 success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 16000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+    let candidate = [
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+        "Program log: Instruction: Deposit",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 190000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4000 of 180000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 22000 of 200000 compute units",
+        "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    ];
+
+    let profile = |logs: &[&str]| {
+        let frames =
+            RawStructuredLog::from_raw_logs(logs.iter().copied().map(RawLog::parse).collect())
+                .expect("Failed to parse structured logs");
+        let mut profile = ComputeProfile::new();
+        profile.add(&frames);
+        profile
+    };
+
+    let diff = ComputeDiff::new(&profile(&baseline), &profile(&candidate));
+    assert_eq!(
+        diff.to_string(),
+        "\
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit): 16000 -> 22000 CU (+6000, +37.5%)
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit)/11111111111111111111111111111111: removed (- CU)
+D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns (Deposit)/TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA#1: added (4000 CU)
+"
+    );
+    assert_eq!(diff.added().count(), 1);
+    assert_eq!(diff.removed().count(), 1);
+
+    assert_eq!(diff.regressions(&RegressionThreshold::new()).count(), 1);
+    let threshold = RegressionThreshold::new().units(1000).percent(50.0);
+    assert_eq!(diff.regressions(&threshold).count(), 0);
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "rpc")]
+
+use std::{fs::File, str::FromStr};
+
+use pretty_assertions::assert_eq;
+use sol_log_parser::{
+    rpc::{GetTransactionResponse, ReturnData, RpcMismatch, SimulateTransactionResponse},
+    LogParseError, StructuredParseOptions,
+};
+use solana_pubkey::Pubkey;
+
+const GET_TRANSACTION: &str = include_str!("fixtures/get_transaction.json");
+const SIMULATE_TRANSACTION: &str = include_str!("fixtures/simulate_transaction.json");
+const RPC_ERROR: &str = include_str!("fixtures/rpc_error.json");
+const GET_TRANSACTION_NULL: &str = include_str!("fixtures/get_transaction_null.json");
+
+#[test]
+fn get_transaction() {
+    let response = GetTransactionResponse::from_json_str(GET_TRANSACTION)
+        .expect("failed to deserialize getTransaction response");
+    let transaction_log = response
+        .transaction_log(StructuredParseOptions::default())
+        .expect("failed to structure transaction logs");
+
+    assert_eq!(
+        transaction_log.transaction.signature.as_deref(),
+        Some("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW")
+    );
+    assert_eq!(transaction_log.transaction.slot, Some(270000000));
+    assert_eq!(transaction_log.transaction.block_time, Some(1717000000));
+    assert_eq!(transaction_log.transaction.frames.len(), 3);
+    assert_eq!(transaction_log.units_consumed, Some(6355));
+    assert_eq!(transaction_log.return_data, None);
+    // builtins do not log their consumption, so the shortfall is expected
+    assert_eq!(transaction_log.mismatches, vec![]);
+
+    let file = File::open("tests/fixtures/get_transaction.json").expect("missing fixture");
+    let from_reader =
+        GetTransactionResponse::from_json_reader(file).expect("failed to read fixture");
+    assert_eq!(from_reader, response);
+}
+
+#[test]
+fn simulate_transaction() {
+    let response = SimulateTransactionResponse::from_json_str(SIMULATE_TRANSACTION)
+        .expect("failed to deserialize simulateTransaction response");
+    let transaction_log = response
+        .transaction_log(StructuredParseOptions::default())
+        .expect("failed to structure transaction logs");
+
+    assert_eq!(transaction_log.transaction.signature, None);
+    assert_eq!(transaction_log.transaction.slot, Some(270000123));
+    assert_eq!(transaction_log.transaction.frames.len(), 1);
+    assert_eq!(transaction_log.transaction.frames[0].cpi_logs.len(), 1);
+    assert_eq!(
+        transaction_log.return_data,
+        Some(ReturnData {
+            program_id: Pubkey::from_str("D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns").unwrap(),
+            data: vec![1, 2, 3, 4],
+        })
+    );
+    assert_eq!(transaction_log.mismatches, vec![]);
+}
+
+#[test]
+fn rpc_mismatches() {
+    let json = SIMULATE_TRANSACTION
+        .replace("\"unitsConsumed\": 12000", "\"unitsConsumed\": 13000")
+        .replace("\"AQIDBA==\", \"base64\"", "\"BQY=\", \"base64\"");
+    let response = SimulateTransactionResponse::from_json_str(&json)
+        .expect("failed to deserialize simulateTransaction response");
+    let transaction_log = response
+        .transaction_log(StructuredParseOptions::default())
+        .expect("failed to structure transaction logs");
+
+    let program_id = Pubkey::from_str("D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns").unwrap();
+    assert_eq!(
+        transaction_log.mismatches,
+        vec![
+            RpcMismatch::UnitsConsumed {
+                reported: 13000,
+                reconstructed: 12000,
+            },
+            RpcMismatch::ReturnData {
+                reported: Some(ReturnData {
+                    program_id,
+                    data: vec![5, 6],
+                }),
+                reconstructed: Some(ReturnData {
+                    program_id,
+                    data: vec![1, 2, 3, 4],
+                }),
+            },
+        ]
+    );
+
+    // the bare `result` is accepted as well as the JSON-RPC envelope
+    let response = SimulateTransactionResponse::from_json_str(
+        r#"{"context": {"slot": 1}, "value": {"err": null, "logs": null}}"#,
+    )
+    .expect("failed to deserialize bare result");
+    assert!(matches!(
+        response.transaction_log(StructuredParseOptions::default()),
+        Err(LogParseError::MissingLogMessages)
+    ));
+
+    assert!(matches!(
+        SimulateTransactionResponse::from_json_str("{"),
+        Err(LogParseError::Json(_))
+    ));
+}
+
+#[test]
+fn rpc_errors() {
+    match GetTransactionResponse::from_json_str(RPC_ERROR) {
+        Err(LogParseError::Rpc { code, message }) => {
+            assert_eq!(code, -32602);
+            assert_eq!(message, "Invalid param: Invalid");
+        }
+        other => panic!("expected an rpc error, got {other:?}"),
+    }
+
+    // an unknown or unconfirmed transaction
+    assert!(matches!(
+        GetTransactionResponse::from_json_str(GET_TRANSACTION_NULL),
+        Err(LogParseError::NullResult)
+    ));
+    let file = File::open("tests/fixtures/get_transaction_null.json").expect("missing fixture");
+    assert!(matches!(
+        GetTransactionResponse::from_json_reader(file),
+        Err(LogParseError::NullResult)
+    ));
+
+    // the serde error names the offending field
+    let json = GET_TRANSACTION.replace("\"slot\": 270000000", "\"slot\": \"x\"");
+    assert_ne!(json, GET_TRANSACTION);
+    let err = GetTransactionResponse::from_json_str(&json).expect_err("invalid slot accepted");
+    assert!(matches!(err, LogParseError::Json(_)));
+    assert_eq!(
+        err.to_string(),
+        "slot: invalid type: string \"x\", expected u64"
+    );
+}
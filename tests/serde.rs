@@ -0,0 +1,166 @@
+#![cfg(feature = "serde")]
+
+use pretty_assertions::assert_eq;
+use sol_log_parser::{
+    serde_helpers::{with_encoding, ByteEncoding, Hex},
+    LogParseError, ParsedLog, ParsedStructuredLog, RawLog, RawStructuredLog,
+};
+
+const LOGS: [&str; 12] = [
+    "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+    "Program log: Instruction: Deposit",
+    "Program 11111111111111111111111111111111 invoke [2]",
+    "Program 11111111111111111111111111111111 success",
+    "Program data: aGVsbG8= c29sYW5h",
+    "Program consumption: 190000 units remaining",
+    "Program return: D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns AQID",
+    "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 10200 of 200000 compute units",
+    "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns success",
+    "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns invoke [1]",
+    "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns consumed 300 of 189800 compute units",
+    "Program D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns failed: custom program error: 0x1770",
+];
+
+#[test]
+fn raw_log_round_trip() {
+    let raw_logs: Vec<_> = LOGS.into_iter().map(RawLog::parse).collect();
+
+    let json = serde_json::to_string(&raw_logs).expect("failed to serialize raw logs");
+    let deserialized: Vec<RawLog> =
+        serde_json::from_str(&json).expect("failed to deserialize raw logs");
+    assert_eq!(deserialized, raw_logs);
+
+    let structured = RawStructuredLog::from_raw_logs(raw_logs).expect("failed to structure logs");
+    let json = serde_json::to_string(&structured).expect("failed to serialize structured logs");
+    let deserialized: Vec<RawStructuredLog> =
+        serde_json::from_str(&json).expect("failed to deserialize structured logs");
+    assert_eq!(deserialized, structured);
+}
+
+#[test]
+fn escaped_log_round_trip() {
+    let log = r#"Program log: "quoted" \ path"#;
+    let raw = RawLog::parse(log);
+    let json = serde_json::to_string(&raw).expect("failed to serialize raw log");
+
+    // raw logs borrow from the input and cannot hold unescaped strings
+    let err = serde_json::from_str::<RawLog>(&json).expect_err("escaped raw log borrowed");
+    assert!(err.to_string().contains("borrowed string"), "{err}");
+
+    let parsed = ParsedLog::from_raw(&raw).expect("failed to parse log");
+    let json = serde_json::to_string(&parsed).expect("failed to serialize parsed log");
+    let deserialized: ParsedLog =
+        serde_json::from_str(&json).expect("failed to deserialize parsed log");
+    assert_eq!(deserialized, parsed);
+}
+
+#[test]
+fn parsed_log_round_trip() {
+    let parsed_logs = LOGS
+        .into_iter()
+        .map(RawLog::parse)
+        .map(|raw| ParsedLog::from_raw(&raw))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("failed to parse logs");
+
+    let json = serde_json::to_string(&parsed_logs).expect("failed to serialize parsed logs");
+    let deserialized: Vec<ParsedLog> =
+        serde_json::from_str(&json).expect("failed to deserialize parsed logs");
+    assert_eq!(deserialized, parsed_logs);
+
+    let structured =
+        ParsedStructuredLog::from_parsed_logs(parsed_logs).expect("failed to structure logs");
+    let json = serde_json::to_string(&structured).expect("failed to serialize structured logs");
+    let deserialized: Vec<ParsedStructuredLog> =
+        serde_json::from_str(&json).expect("failed to deserialize structured logs");
+    assert_eq!(deserialized, structured);
+}
+
+#[test]
+fn parsed_log_encoding() {
+    let parsed_logs = LOGS
+        .into_iter()
+        .map(RawLog::parse)
+        .map(|raw| ParsedLog::from_raw(&raw))
+        .collect::<Result<Vec<_>, LogParseError>>()
+        .expect("failed to parse logs");
+    let structured =
+        ParsedStructuredLog::from_parsed_logs(parsed_logs).expect("failed to structure logs");
+
+    let value = serde_json::to_value(&structured[0]).expect("failed to serialize structured log");
+    assert_eq!(
+        value["program_id"],
+        "D4SghRBTyA7HQSEH89uT9LgCs1TTtrPptwuqm1sLSsns"
+    );
+
+    assert_eq!(value["return_data"], "AQID");
+    assert_eq!(
+        value["data_logs"][0]["data"],
+        serde_json::json!(["aGVsbG8=", "c29sYW5h"])
+    );
+
+    let value = with_encoding(ByteEncoding::Hex, || serde_json::to_value(&structured[0]))
+        .expect("failed to serialize structured log");
+    assert_eq!(value["return_data"], "010203");
+    assert_eq!(
+        value["data_logs"][0]["data"],
+        serde_json::json!(["68656c6c6f", "736f6c616e61"])
+    );
+    assert_eq!(
+        serde_json::to_value(Hex(&structured[0])).expect("failed to serialize structured log"),
+        value
+    );
+
+    // the encoding is restored once the closure returns
+    let value = serde_json::to_value(&structured[0]).expect("failed to serialize structured log");
+    assert_eq!(value["return_data"], "AQID");
+
+    let json = serde_json::to_string(&Hex(&structured)).expect("failed to serialize logs");
+    let Hex(deserialized): Hex<Vec<ParsedStructuredLog>> =
+        serde_json::from_str(&json).expect("failed to deserialize structured logs");
+    assert_eq!(deserialized, structured);
+    serde_json::from_str::<Vec<ParsedStructuredLog>>(&json)
+        .expect_err("hex payloads decoded as base64");
+}
+
+#[test]
+fn hex_encoding() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Payload {
+        #[serde(with = "sol_log_parser::serde_helpers::hex::bytes")]
+        data: Vec<u8>,
+        #[serde(with = "sol_log_parser::serde_helpers::hex::option_bytes")]
+        return_data: Option<Vec<u8>>,
+        #[serde(with = "sol_log_parser::serde_helpers::hex::bytes_vec")]
+        fields: Vec<Vec<u8>>,
+    }
+
+    let payload = Payload {
+        data: vec![1, 2, 3],
+        return_data: Some(b"hello".to_vec()),
+        fields: vec![b"hello".to_vec(), b"solana".to_vec()],
+    };
+
+    let value = serde_json::to_value(&payload).expect("failed to serialize payload");
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "data": "010203",
+            "return_data": "68656c6c6f",
+            "fields": ["68656c6c6f", "736f6c616e61"],
+        })
+    );
+
+    let deserialized: Payload =
+        serde_json::from_value(value).expect("failed to deserialize payload");
+    assert_eq!(deserialized, payload);
+
+    for invalid in ["0", "0g", "+f"] {
+        serde_json::from_value::<Payload>(serde_json::json!({
+            "data": invalid,
+            "return_data": null,
+            "fields": [],
+        }))
+        .expect_err("invalid hex decoded");
+    }
+}